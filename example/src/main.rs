@@ -102,12 +102,9 @@ fn server(port: u16) {
 
         // Process connections events.
         for event in server.receive_events() {
-          match (event.old_state(), event.info().state()) {
+          match event.transition() {
             // A client is about to connect, accept it.
-            (
-              ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-              ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-            ) => {
+            ConnectionTransition::IncomingRequest => {
               let result = server.accept(event.connection());
               println!("GnsSocket<Server>: accepted new client: {:#?}.", result);
               if result.is_ok() {
@@ -124,13 +121,10 @@ fn server(port: u16) {
 
             // A client is connected, we previously accepted it and don't do anything here.
             // In a more sophisticated scenario we could initial sending some messages.
-            (
-              ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-              ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected,
-            ) => {
+            ConnectionTransition::Connected => {
             }
 
-            (_, ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer | ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally) => {
+            ConnectionTransition::ClosedByPeer | ConnectionTransition::ProblemDetectedLocally => {
               // Remove the client from the list and close the connection.
               let conn = event.connection();
               println!("GnsSocket<Server>: {:#?} disconnected", conn);
@@ -142,13 +136,13 @@ fn server(port: u16) {
               );
               connected_clients.remove(&conn);
               // Make sure we cleanup the connection, mandatory as per GNS doc.
-              let _ = server.close_connection(conn, 0, None, false);
+              let _ = server.close_connection(conn, EndReason::App(0), None, false);
             }
 
             // A client state is changing, perhaps disconnecting
-            // If a client disconnected and it's connection get cleaned up, its state goes back to `ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None`
-            (previous, current) => {
-              println!("GnsSocket<Server>: {:#?} => {:#?}.", previous, current);
+            // If a client disconnected and it's connection get cleaned up, its state goes back to `ConnectionState::None`, i.e. `ConnectionTransition::Destroyed`
+            transition => {
+              println!("GnsSocket<Server>: {:#?}.", transition);
             }
           }
         }
@@ -221,26 +215,20 @@ fn client(port: u16) {
 
         let mut quit = false;
         for event in client.receive_events() {
-            match (event.old_state(), event.info().state()) {
-                (
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                ) => {
+            match event.transition() {
+                ConnectionTransition::Connecting => {
                     println!("GnsSocket<Client>: connecting to server.");
                 }
-                (
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected,
-                ) => {
+                ConnectionTransition::Connected => {
                     println!("GnsSocket<Client>: connected to server.");
                 }
-                (_, ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer | ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally) => {
+                ConnectionTransition::ClosedByPeer | ConnectionTransition::ProblemDetectedLocally => {
                   // We got disconnected or lost the connection.
                   println!("GnsSocket<Client>: ET phone home.");
                   quit = true;
                 }
-                (previous, current) => {
-                    println!("GnsSocket<Client>: {:#?} => {:#?}.", previous, current);
+                transition => {
+                    println!("GnsSocket<Client>: {:#?}.", transition);
                 }
             }
         }
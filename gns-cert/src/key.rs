@@ -0,0 +1,136 @@
+//! Ed25519 keys in the OpenSSH formats GNS parses, and PEM armoring.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+const KEY_TYPE: &str = "ssh-ed25519";
+const MAGIC: &[u8] = b"openssh-key-v1\0";
+pub const PRIVATE_KEY_LABEL: &str = "OPENSSH PRIVATE KEY";
+pub const CERT_LABEL: &str = "STEAMDATAGRAM CERT";
+
+/// Wrap `data` in a PEM block, 70 base64 columns per line like OpenSSH.
+pub fn pem(label: &str, data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut out = format!("-----BEGIN {label}-----\n");
+    for line in encoded.as_bytes().chunks(70) {
+        out.push_str(core::str::from_utf8(line).expect("base64 is ascii; qed;"));
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {label}-----\n"));
+    out
+}
+
+/// Decode the first PEM block labelled `label` in `text`.
+pub fn unpem(text: &str, label: &str) -> Result<Vec<u8>, String> {
+    let begin = format!("-----BEGIN {label}-----");
+    let end = format!("-----END {label}-----");
+    let start = text
+        .find(&begin)
+        .ok_or_else(|| format!("no {label} block"))?
+        + begin.len();
+    let len = text[start..]
+        .find(&end)
+        .ok_or_else(|| format!("unterminated {label} block"))?;
+    let body: String = text[start..start + len]
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| format!("invalid {label} block: {e}"))
+}
+
+/// Identifier GNS gives a CA public key: the first 8 bytes of its SHA-256,
+/// little endian.
+pub fn key_id(key: &VerifyingKey) -> u64 {
+    let digest = Sha256::digest(key.as_bytes());
+    u64::from_le_bytes(digest[..8].try_into().expect("digest is 32 bytes; qed;"))
+}
+
+fn put_string(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+fn get_string<'a>(input: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let truncated = || "truncated private key".to_string();
+    let len = input.get(..4).ok_or_else(truncated)?;
+    let len = u32::from_be_bytes(len.try_into().expect("4 bytes; qed;")) as usize;
+    let data = input.get(4..4 + len).ok_or_else(truncated)?;
+    *input = &input[4 + len..];
+    Ok(data)
+}
+
+fn public_blob(key: &VerifyingKey) -> Vec<u8> {
+    let mut blob = Vec::new();
+    put_string(&mut blob, KEY_TYPE.as_bytes());
+    put_string(&mut blob, key.as_bytes());
+    blob
+}
+
+/// `ssh-ed25519 <base64> <comment>` line.
+pub fn public_key_line(key: &VerifyingKey, comment: &str) -> String {
+    format!("{KEY_TYPE} {} {comment}", STANDARD.encode(public_blob(key)))
+}
+
+/// Unencrypted `openssh-key-v1` private key, PEM armored.
+pub fn private_key_pem(key: &SigningKey, comment: &str) -> String {
+    let public = key.verifying_key();
+    let check = OsRng.next_u32().to_be_bytes();
+    let mut secret = Vec::new();
+    secret.extend_from_slice(&check);
+    secret.extend_from_slice(&check);
+    put_string(&mut secret, KEY_TYPE.as_bytes());
+    put_string(&mut secret, public.as_bytes());
+    put_string(&mut secret, &key.to_keypair_bytes());
+    put_string(&mut secret, comment.as_bytes());
+    // Padding to the cipher block size, 8 for "none".
+    for pad in 1..=((8 - secret.len() % 8) % 8) as u8 {
+        secret.push(pad);
+    }
+
+    let mut blob = MAGIC.to_vec();
+    put_string(&mut blob, b"none");
+    put_string(&mut blob, b"none");
+    put_string(&mut blob, b"");
+    blob.extend_from_slice(&1u32.to_be_bytes());
+    put_string(&mut blob, &public_blob(&public));
+    put_string(&mut blob, &secret);
+    pem(PRIVATE_KEY_LABEL, &blob)
+}
+
+/// Parse a key written by [`private_key_pem`] (or `ssh-keygen -t ed25519`
+/// without passphrase).
+pub fn parse_private_key(text: &str) -> Result<SigningKey, String> {
+    let blob = unpem(text, PRIVATE_KEY_LABEL)?;
+    let mut input = blob
+        .strip_prefix(MAGIC)
+        .ok_or("not an openssh-key-v1 private key")?;
+    if get_string(&mut input)? != b"none" {
+        return Err("encrypted private keys are not supported".into());
+    }
+    get_string(&mut input)?; // kdfname
+    get_string(&mut input)?; // kdfoptions
+    input = input.get(4..).ok_or("truncated private key")?; // key count
+    get_string(&mut input)?; // public key
+    let mut secret = get_string(&mut input)?;
+    let (check1, check2) = (secret.get(..4), secret.get(4..8));
+    if check1.is_none() || check1 != check2 {
+        return Err("corrupted private key".into());
+    }
+    secret = &secret[8..];
+    if get_string(&mut secret)? != KEY_TYPE.as_bytes() {
+        return Err("not an ed25519 private key".into());
+    }
+    get_string(&mut secret)?; // public key
+    let keypair: &[u8; 64] = get_string(&mut secret)?
+        .try_into()
+        .map_err(|_| "invalid ed25519 private key length")?;
+    SigningKey::from_keypair_bytes(keypair).map_err(|e| format!("invalid ed25519 key: {e}"))
+}
+
+pub fn generate() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
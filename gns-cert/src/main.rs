@@ -0,0 +1,214 @@
+//! `gns-cert`: offline CA for GameNetworkingSockets identities.
+//!
+//! Generates CA keypairs and issues Ed25519 certificates in the PEM format
+//! loaded by `GnsAuth::set_certificate`. Peers only accept the certificates
+//! if the CA key is trusted by their GNS build, i.e. listed in the file named
+//! by `GNS_TRUSTED_CA_KEYS` when building `game-networking-sockets-sys`.
+
+mod key;
+mod proto;
+
+use clap::{Args, Parser, Subcommand};
+use ed25519_dalek::Signer;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a CA keypair, written to `<OUT>.key` and `<OUT>.pub`.
+    GenCa {
+        /// Path prefix of the generated files.
+        #[arg(long, default_value = "ca")]
+        out: PathBuf,
+        /// Comment stored alongside the key.
+        #[arg(long, default_value = "gns-cert CA")]
+        comment: String,
+    },
+    /// Issue a certificate signed by a CA.
+    Issue(Issue),
+}
+
+#[derive(Args)]
+struct Issue {
+    /// Private key of the signing CA, as written by `gen-ca`.
+    #[arg(long)]
+    ca_key: PathBuf,
+    /// Identity to certify, e.g. `str:my-server`. A fresh keypair is
+    /// generated and printed after the certificate.
+    #[arg(long, required_unless_present = "request", conflicts_with = "request")]
+    identity: Option<String>,
+    /// Certificate request from `GnsAuth::certificate_request`, to certify
+    /// a key kept by GNS instead of generating one.
+    #[arg(long)]
+    request: Option<PathBuf>,
+    /// Validity of the certificate, in days from now.
+    #[arg(long, default_value_t = 365)]
+    days: u32,
+    /// Restrict the certificate to an app ID (repeatable).
+    #[arg(long = "app-id")]
+    app_ids: Vec<u32>,
+    /// Restrict the certificate to a POP code such as `iad` (repeatable).
+    #[arg(long = "pop")]
+    pops: Vec<String>,
+    /// Output file, stdout if omitted.
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// `CalculateSteamNetworkingPOPIDFromString` of the GNS headers.
+fn pop_id(code: &str) -> Result<u32, String> {
+    let bytes = code.as_bytes();
+    if !(3..=4).contains(&bytes.len()) || !bytes.iter().all(u8::is_ascii_alphanumeric) {
+        return Err(format!(
+            "invalid POP code {code:?}, expected 3 or 4 characters"
+        ));
+    }
+    let byte = |i: usize| u32::from(bytes.get(i).copied().unwrap_or(0));
+    Ok(byte(0) << 16 | byte(1) << 8 | byte(2) | byte(3) << 24)
+}
+
+fn check_identity(identity: &str) -> Result<(), String> {
+    match identity.split_once(':') {
+        Some((kind, value)) if !kind.is_empty() && !value.is_empty() => Ok(()),
+        _ => Err(format!(
+            "invalid identity {identity:?}, expected `<type>:<value>` such as `str:my-server`"
+        )),
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Like [`write`], for files holding a private key: on unix they are only
+/// readable by their owner.
+fn write_private(path: &Path, contents: &str) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// `path` with `suffix` appended, unlike `with_extension` which would
+/// replace a dotted part of the prefix.
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+fn gen_ca(out: &Path, comment: &str) -> Result<(), String> {
+    let ca = key::generate();
+    let public = ca.verifying_key();
+    write_private(&suffixed(out, ".key"), &key::private_key_pem(&ca, comment))?;
+    write(
+        &suffixed(out, ".pub"),
+        &format!("{}\n", key::public_key_line(&public, comment)),
+    )?;
+    println!("CA key ID: {}", key::key_id(&public));
+    println!("{}", key::public_key_line(&public, comment));
+    Ok(())
+}
+
+fn issue(args: &Issue) -> Result<(), String> {
+    let ca_text = String::from_utf8(read(&args.ca_key)?).map_err(|e| e.to_string())?;
+    let ca = key::parse_private_key(&ca_text)?;
+
+    let (mut cert, identity_key) = match (&args.request, &args.identity) {
+        (Some(path), _) => {
+            let request = read(path)?;
+            let cert = proto::find_bytes(&request, proto::request::CERT)?
+                .ok_or("certificate request holds no certificate")?;
+            // Validity is set by the CA, not by whoever made the request.
+            let cert = proto::Writer::without(
+                cert,
+                &[proto::cert::TIME_CREATED, proto::cert::TIME_EXPIRY],
+            )?;
+            (cert, None)
+        }
+        (None, Some(identity)) => {
+            check_identity(identity)?;
+            let identity_key = key::generate();
+            let mut cert = proto::Writer::default();
+            cert.varint(proto::cert::KEY_TYPE, proto::cert::KEY_TYPE_ED25519)
+                .bytes(
+                    proto::cert::KEY_DATA,
+                    identity_key.verifying_key().as_bytes(),
+                )
+                .bytes(proto::cert::IDENTITY_STRING, identity.as_bytes());
+            (cert, Some((identity_key, identity)))
+        }
+        (None, None) => unreachable!("clap requires one of identity and request; qed;"),
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs();
+    let expiry = now + u64::from(args.days) * 24 * 60 * 60;
+    let time = |t: u64| u32::try_from(t).map_err(|_| "time out of certificate range".to_string());
+    cert.fixed32(proto::cert::TIME_CREATED, time(now)?)
+        .fixed32(proto::cert::TIME_EXPIRY, time(expiry)?);
+    for &app_id in &args.app_ids {
+        cert.varint(proto::cert::APP_IDS, u64::from(app_id));
+    }
+    for pop in &args.pops {
+        cert.fixed32(proto::cert::GAMESERVER_DATACENTER_IDS, pop_id(pop)?);
+    }
+    let cert = cert.finish();
+
+    let signed = proto::Writer::default()
+        .bytes(proto::signed::CERT, &cert)
+        .fixed64(proto::signed::CA_KEY_ID, key::key_id(&ca.verifying_key()))
+        .bytes(proto::signed::CA_SIGNATURE, &ca.sign(&cert).to_bytes())
+        .finish();
+
+    let mut output = key::pem(key::CERT_LABEL, &signed);
+    let private = identity_key.is_some();
+    if let Some((identity_key, identity)) = identity_key {
+        output.push_str(&key::private_key_pem(&identity_key, identity));
+    }
+    match &args.out {
+        Some(path) if private => write_private(path, &output),
+        Some(path) => write(path, &output),
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::GenCa { out, comment } => gen_ca(out, comment),
+        Command::Issue(args) => issue(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
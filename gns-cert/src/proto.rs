@@ -0,0 +1,155 @@
+//! Minimal protobuf wire encoding of the GNS certificate messages.
+//!
+//! Field numbers follow `steamnetworkingsockets_messages_certs.proto` of
+//! GameNetworkingSockets, only the fields this tool emits are listed.
+
+/// `CMsgSteamDatagramCertificate`.
+pub mod cert {
+    pub const KEY_TYPE: u32 = 1;
+    pub const KEY_DATA: u32 = 2;
+    pub const GAMESERVER_DATACENTER_IDS: u32 = 5;
+    pub const TIME_CREATED: u32 = 8;
+    pub const TIME_EXPIRY: u32 = 9;
+    pub const APP_IDS: u32 = 10;
+    pub const IDENTITY_STRING: u32 = 12;
+
+    /// `CMsgSteamDatagramCertificate.EKeyType.ED25519`.
+    pub const KEY_TYPE_ED25519: u64 = 1;
+}
+
+/// `CMsgSteamDatagramCertificateSigned`.
+pub mod signed {
+    pub const CERT: u32 = 4;
+    pub const CA_KEY_ID: u32 = 5;
+    pub const CA_SIGNATURE: u32 = 6;
+}
+
+/// `CMsgSteamDatagramCertificateRequest`.
+pub mod request {
+    pub const CERT: u32 = 1;
+}
+
+const VARINT: u32 = 0;
+const FIXED64: u32 = 1;
+const LEN: u32 = 2;
+const FIXED32: u32 = 5;
+
+/// Append-only protobuf message writer.
+#[derive(Default)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    /// Start from an already encoded message, minus its `dropped` fields so
+    /// they can be written again without being duplicated.
+    pub fn without(mut message: &[u8], dropped: &[u32]) -> Result<Self, String> {
+        let mut writer = Self::default();
+        while !message.is_empty() {
+            let field = next_field(&mut message)?;
+            if !dropped.contains(&field.number) {
+                writer.0.extend_from_slice(field.encoded);
+            }
+        }
+        Ok(writer)
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn tag(&mut self, field: u32, wire: u32) {
+        self.raw_varint(u64::from(field << 3 | wire));
+    }
+
+    pub fn varint(&mut self, field: u32, value: u64) -> &mut Self {
+        self.tag(field, VARINT);
+        self.raw_varint(value);
+        self
+    }
+
+    pub fn fixed32(&mut self, field: u32, value: u32) -> &mut Self {
+        self.tag(field, FIXED32);
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn fixed64(&mut self, field: u32, value: u64) -> &mut Self {
+        self.tag(field, FIXED64);
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.tag(field, LEN);
+        self.raw_varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub fn finish(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.0)
+    }
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first().ok_or("truncated varint")?;
+        *input = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint too long".into())
+}
+
+/// A field split off an encoded message by [`next_field`].
+struct Field<'a> {
+    number: u32,
+    /// The whole field, tag included.
+    encoded: &'a [u8],
+    /// The value of a length-delimited field.
+    bytes: Option<&'a [u8]>,
+}
+
+fn next_field<'a>(message: &mut &'a [u8]) -> Result<Field<'a>, String> {
+    let start = *message;
+    let key = read_varint(message)?;
+    let mut bytes = None;
+    let skip = match (key & 7) as u32 {
+        VARINT => {
+            read_varint(message)?;
+            0
+        }
+        FIXED64 => 8,
+        FIXED32 => 4,
+        LEN => {
+            let len = read_varint(message)? as usize;
+            bytes = Some(message.get(..len).ok_or("truncated field")?);
+            len
+        }
+        wire => return Err(format!("unsupported wire type {wire}")),
+    };
+    *message = message.get(skip..).ok_or("truncated field")?;
+    Ok(Field {
+        number: u32::try_from(key >> 3).map_err(|_| "field number out of range")?,
+        encoded: &start[..start.len() - message.len()],
+        bytes,
+    })
+}
+
+/// Last occurrence of the length-delimited `field` of `message`.
+pub fn find_bytes(mut message: &[u8], field: u32) -> Result<Option<&[u8]>, String> {
+    let mut found = None;
+    while !message.is_empty() {
+        let next = next_field(&mut message)?;
+        if next.number == field {
+            found = next.bytes.or(found);
+        }
+    }
+    Ok(found)
+}
@@ -252,6 +252,72 @@ fn git_clone(repo_url: &str, dst: &Path, commit: Option<&str>) {
     ]));
 }
 
+/// CA keys listed in the file named by `GNS_TRUSTED_CA_KEYS`, one OpenSSH
+/// `ssh-ed25519` line each, as written to `<OUT>.pub` by `gns-cert gen-ca`.
+fn trusted_ca_keys() -> Vec<String> {
+    println!("cargo::rerun-if-env-changed=GNS_TRUSTED_CA_KEYS");
+    let Ok(path) = std::env::var("GNS_TRUSTED_CA_KEYS") else {
+        return Vec::new();
+    };
+    println!("cargo::rerun-if-changed={path}");
+    let keys = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("GNS_TRUSTED_CA_KEYS: failed to read '{path}': {e}"));
+    keys.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            if !line.starts_with("ssh-ed25519 ") || line.contains(['"', '\\']) {
+                panic!("GNS_TRUSTED_CA_KEYS: not an ed25519 public key in '{path}': {line:?}");
+            }
+            line.to_owned()
+        })
+        .collect()
+}
+
+/// Recursively look for the file called `name` under `dir`.
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|file| file == name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Make the GNS sources in `gns_src_dir` trust `keys` on top of the CA keys
+/// hardcoded in their cert store. The flat API has no call to add trusted
+/// keys at runtime, so they are appended to that hardcoded list.
+fn trust_ca_keys(gns_src_dir: &Path, keys: &[String]) {
+    let certstore = find_file(
+        &gns_src_dir.join("src"),
+        "steamnetworkingsockets_certstore.cpp",
+    )
+    .expect("GNS_TRUSTED_CA_KEYS: GameNetworkingSockets has no cert store source");
+    let source = std::fs::read_to_string(&certstore).unwrap();
+    // End of the first hardcoded key, a `"ssh-ed25519 ..."` string literal.
+    let end = source
+        .find("\"ssh-ed25519 ")
+        .and_then(|start| Some(start + 1 + source[start + 1..].find('"')? + 1))
+        .unwrap_or_else(|| {
+            panic!(
+                "GNS_TRUSTED_CA_KEYS: no hardcoded CA key found in '{}', this \
+                 GameNetworkingSockets version is not supported",
+                certstore.display()
+            )
+        });
+    let added: String = keys.iter().map(|key| format!(",\n\t\"{key}\"")).collect();
+    std::fs::write(
+        &certstore,
+        format!("{}{added}{}", &source[..end], &source[end..]),
+    )
+    .unwrap();
+}
+
 fn main() {
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
     let target_env = std::env::var("CARGO_CFG_TARGET_ENV").unwrap();
@@ -335,6 +401,17 @@ fn main() {
         .write_to_file(out_dir.join("bindings.rs"))
         .expect("Couldn't write bindings!");
 
+    let trusted_ca_keys = trusted_ca_keys();
+    std::fs::write(
+        out_dir.join("trusted_ca_keys.rs"),
+        format!(
+            "/// CA keys trusted on top of the GNS built-in ones, from the file named\n\
+             /// by `GNS_TRUSTED_CA_KEYS` at build time.\n\
+             pub const TRUSTED_CA_KEYS: &[&str] = &{trusted_ca_keys:?};\n"
+        ),
+    )
+    .expect("Couldn't write trusted CA keys!");
+
     if std::env::var("DOCS_RS").is_ok() {
         // We're building docs on docs.rs, and don't actually need to compile. Instead, just
         // generate bindings and let docs build from that.
@@ -345,12 +422,33 @@ fn main() {
 
     link("GameNetworkingSockets_s");
 
-    let gns_src_dir = if &target_os == "windows" && &target_env == "msvc" {
+    let p2p = std::env::var("CARGO_FEATURE_P2P").is_ok();
+    if p2p {
+        let external_dir = gns_src_dir.join("src").join("external");
+        if !external_dir.join("webrtc").join("api").exists()
+            || !external_dir.join("abseil").join("absl").exists()
+        {
+            panic!(
+                "the `p2p` feature needs the webrtc and abseil sources in '{}', which are not \
+                 packaged on crates.io; depend on gns-sys from a git checkout and run \
+                 `git submodule update --init --recursive`",
+                external_dir.display()
+            );
+        }
+        link_search("build/src/external/steamwebrtc");
+        link("static=steamwebrtc");
+    }
+
+    let msvc = &target_os == "windows" && &target_env == "msvc";
+    if msvc {
         println!(
             "cargo::rerun-if-changed={}",
             gns_src_dir.join("vcpkg.json").display()
         );
+    }
 
+    // Trusting extra CA keys patches the sources, which is also only done in OUT_DIR.
+    let gns_src_dir = if msvc || !trusted_ca_keys.is_empty() {
         // TODO: We can't make changes outside of OUT_DIR, but we need to clone/install vcpkg,
         //  and _only_ on Windows. Upstream GameNetworkingSockets will only find vcpkg if it is
         //  cloned to the root of it's src/ dir; we may want to submit a patch that will let it
@@ -360,6 +458,9 @@ fn main() {
             std::fs::remove_dir_all(&new_dir).unwrap();
         }
         dircpy::copy_dir(&gns_src_dir, &new_dir).unwrap();
+        if !trusted_ca_keys.is_empty() {
+            trust_ca_keys(&new_dir, &trusted_ca_keys);
+        }
         new_dir
     } else {
         gns_src_dir
@@ -453,6 +554,9 @@ fn main() {
     link_stdlib();
 
     c.static_crt(false);
+    if p2p {
+        c.define("USE_STEAMWEBRTC", "ON");
+    }
     c.define("BUILD_STATIC_LIB", "ON");
     c.define("BUILD_SHARED_LIB", "OFF");
     if target_os == "macos" {
@@ -9,3 +9,4 @@
 #![allow(clippy::missing_transmute_annotations)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+include!(concat!(env!("OUT_DIR"), "/trusted_ca_keys.rs"));
@@ -0,0 +1,203 @@
+//! Certificate-based authentication of the local identity and of peers.
+//!
+//! GNS authenticates a peer by the certificate it presents, which must be
+//! signed by a CA the library trusts. The flat API exposes no call to add
+//! trusted CA keys at runtime, so they are built in: point the
+//! `GNS_TRUSTED_CA_KEYS` environment variable at a file of `ssh-ed25519`
+//! public keys, such as the `<OUT>.pub` written by `gns-cert gen-ca`, when
+//! building `game-networking-sockets-sys`. [`GnsAuth::trusted_ca_keys`]
+//! lists the keys a build picked up.
+
+use crate::config::{self, ConfigKey, ConfigScope};
+use crate::sys::*;
+use crate::{get_interface, GnsConfigValue, GnsError, GnsGlobal, GnsResult, GnsUtils};
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+use std::sync::Mutex;
+
+/// The global values set by [`GnsAuth::require_authentication`].
+const ALLOW_WITHOUT_AUTH: [ConfigKey<i32>; 2] = [
+    config::IP_ALLOW_WITHOUT_AUTH,
+    config::IP_LOCALHOST_ALLOW_WITHOUT_AUTH,
+];
+
+/// Authentication operations, see [`GnsGlobal::auth`].
+pub struct GnsAuth {
+    utils: &'static GnsUtils,
+    /// The [`ALLOW_WITHOUT_AUTH`] global values while authentication is
+    /// required, `None` for those left to their default.
+    saved: Mutex<Option<[Option<i32>; 2]>>,
+}
+
+/// Snapshot of the local authentication state, see [`GnsAuth::status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GnsAuthStatus {
+    pub availability: ESteamNetworkingAvailability,
+    /// Human readable detail, e.g. why the certificate could not be used.
+    pub debug: String,
+}
+
+impl GnsAuthStatus {
+    /// `true` once a certificate is loaded and usable.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.availability == ESteamNetworkingAvailability::k_ESteamNetworkingAvailability_Current
+    }
+}
+
+/// Read the NUL-terminated message GNS wrote to `error`.
+fn err_msg(error: &SteamNetworkingErrMsg) -> GnsError {
+    let msg = unsafe { CStr::from_ptr(error.as_ptr()) };
+    GnsError::Auth(msg.to_string_lossy().into_owned())
+}
+
+impl GnsAuth {
+    pub(crate) fn new(utils: &'static GnsUtils) -> Self {
+        GnsAuth {
+            utils,
+            saved: Mutex::new(None),
+        }
+    }
+
+    /// Start obtaining a certificate for the local identity, if not already
+    /// underway. Returns the availability right after the call.
+    #[inline]
+    pub fn init(&self) -> ESteamNetworkingAvailability {
+        unsafe { SteamAPI_ISteamNetworkingSockets_InitAuthentication(get_interface()) }
+    }
+
+    /// The CA keys trusted on top of the GNS built-in ones, as given through
+    /// `GNS_TRUSTED_CA_KEYS` when building `game-networking-sockets-sys`.
+    ///
+    /// There is no way to add one at runtime: the GNS cert store is only
+    /// filled from its hardcoded list, which the flat API does not expose.
+    #[inline]
+    pub fn trusted_ca_keys(&self) -> &'static [&'static str] {
+        TRUSTED_CA_KEYS
+    }
+
+    pub fn status(&self) -> GnsAuthStatus {
+        let mut details = SteamNetAuthenticationStatus_t::default();
+        let availability = unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetAuthenticationStatus(get_interface(), &mut details)
+        };
+        GnsAuthStatus {
+            availability,
+            debug: unsafe { CStr::from_ptr(details.m_debugMsg.as_ptr()) }
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+
+    /// Load the signed certificate and private key of the local identity, as
+    /// PEM blocks in a single blob. The certificate must have been issued for
+    /// the identity GNS was initialized with.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Auth`] with the reason given by GNS if the blob is
+    /// malformed, expired, or does not match the local identity.
+    pub fn set_certificate(&self, certificate: &[u8]) -> GnsResult<()> {
+        let mut error: SteamNetworkingErrMsg = unsafe { MaybeUninit::zeroed().assume_init() };
+        if unsafe {
+            SteamAPI_ISteamNetworkingSockets_SetCertificate(
+                get_interface(),
+                certificate.as_ptr() as _,
+                certificate.len() as _,
+                &mut error,
+            )
+        } {
+            Ok(())
+        } else {
+            Err(err_msg(&error))
+        }
+    }
+
+    /// A certificate request for the local identity, to be signed by a CA and
+    /// loaded back with [`GnsAuth::set_certificate`]. The matching private key
+    /// is kept by GNS.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Auth`] with the reason given by GNS, e.g. when the
+    /// local identity is not known yet.
+    pub fn certificate_request(&self) -> GnsResult<Vec<u8>> {
+        let mut error: SteamNetworkingErrMsg = unsafe { MaybeUninit::zeroed().assume_init() };
+        let mut len = 0;
+        // First call sizes the blob, second one fills it.
+        if !unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetCertificateRequest(
+                get_interface(),
+                &mut len,
+                core::ptr::null_mut(),
+                &mut error,
+            )
+        } {
+            return Err(err_msg(&error));
+        }
+        let mut blob = vec![0u8; len.max(0) as usize];
+        if !unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetCertificateRequest(
+                get_interface(),
+                &mut len,
+                blob.as_mut_ptr() as _,
+                &mut error,
+            )
+        } {
+            return Err(err_msg(&error));
+        }
+        blob.truncate(len.max(0) as usize);
+        Ok(blob)
+    }
+
+    /// Refuse, or allow again, IP connections with peers that cannot present
+    /// a certificate signed by a trusted CA, localhost included. Applies to
+    /// connections created afterwards.
+    ///
+    /// This sets [`IP_ALLOW_WITHOUT_AUTH`](config::IP_ALLOW_WITHOUT_AUTH)
+    /// and [`IP_LOCALHOST_ALLOW_WITHOUT_AUTH`](config::IP_LOCALHOST_ALLOW_WITHOUT_AUTH)
+    /// globally. Lifting the requirement restores the global values they had
+    /// when it was set, and does nothing if it is not set.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if GNS rejects the setting.
+    pub fn require_authentication(&self, require: bool) -> GnsResult<()> {
+        let mut saved = self.saved.lock().unwrap();
+        if !require {
+            let Some(values) = saved.take() else {
+                return Ok(());
+            };
+            for (key, value) in ALLOW_WITHOUT_AUTH.into_iter().zip(values) {
+                match value {
+                    Some(value) => self.utils.set_config(ConfigScope::Global, key, value)?,
+                    None => self.utils.reset_config(ConfigScope::Global, key)?,
+                }
+            }
+            return Ok(());
+        }
+        if saved.is_none() {
+            let mut values = [None; 2];
+            for (value, key) in values.iter_mut().zip(ALLOW_WITHOUT_AUTH) {
+                let global = self
+                    .utils
+                    .get_config_value(ConfigScope::Global, key.value())?;
+                *value = match global {
+                    // Inherited on the global scope: never set, the default.
+                    (_, true) => None,
+                    (GnsConfigValue::Int32(set), false) => Some(set),
+                    _ => return Err(GnsError::Config("config value type mismatch")),
+                };
+            }
+            *saved = Some(values);
+        }
+        for key in ALLOW_WITHOUT_AUTH {
+            self.utils.set_config(ConfigScope::Global, key, 0)?;
+        }
+        Ok(())
+    }
+}
+
+impl GnsGlobal {
+    #[inline]
+    pub fn auth(&self) -> &GnsAuth {
+        &self.auth
+    }
+}
@@ -0,0 +1,194 @@
+//! Backpressure on the messages queued for sending.
+//!
+//! GNS queues every message it is handed until its send buffer is full, so a
+//! slow peer first shows up as a queue growing behind it. A [`SendBudget`]
+//! bounds the queue of a connection, or of one of its lanes, before the
+//! message reaches GNS: a reliable message over budget is handed back to be
+//! sent later, an unreliable one is dropped since a fresher one will follow.
+
+use crate::sys::EResult;
+use crate::{
+    GnsConnection, GnsError, GnsLaneId, GnsMessageNumber, GnsNetworkMessage, GnsResult, GnsSocket,
+    IsReady, SendFlags, ToSend,
+};
+use std::time::Duration;
+
+/// Limits of a send queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendBudget {
+    /// Longest a message queued now may wait before being sent.
+    pub max_queue_time: Duration,
+    /// Most bytes queued, reliable and unreliable, the message included. A
+    /// message is never over this limit on an empty queue, whatever its size.
+    pub max_pending_bytes: u32,
+}
+
+impl SendBudget {
+    #[inline]
+    pub const fn new(max_queue_time: Duration, max_pending_bytes: u32) -> Self {
+        SendBudget {
+            max_queue_time,
+            max_pending_bytes,
+        }
+    }
+
+    /// Whether a queue of `pending_bytes` waiting `queue_time` is over budget
+    /// for `size` more bytes.
+    fn exceeded(&self, queue_time: Duration, pending_bytes: u32, size: u32) -> bool {
+        queue_time > self.max_queue_time
+            || (pending_bytes > 0 && pending_bytes.saturating_add(size) > self.max_pending_bytes)
+    }
+}
+
+impl Default for SendBudget {
+    /// 200ms and half of the default send buffer of GNS.
+    fn default() -> Self {
+        SendBudget::new(Duration::from_millis(200), 256 * 1024)
+    }
+}
+
+/// Budgets of a connection and of some of its lanes, see
+/// [`GnsSocket::send_with_budget`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SendBudgets {
+    connection: Option<SendBudget>,
+    lanes: Vec<(GnsLaneId, SendBudget)>,
+}
+
+impl SendBudgets {
+    /// No limit, until some are added.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the queue of the connection as a whole.
+    #[inline]
+    pub fn connection(mut self, budget: SendBudget) -> Self {
+        self.connection = Some(budget);
+        self
+    }
+
+    /// Limit the queue of `lane`, replacing its previous budget.
+    pub fn lane(mut self, lane: GnsLaneId, budget: SendBudget) -> Self {
+        self.lanes.retain(|&(l, _)| l != lane);
+        self.lanes.push((lane, budget));
+        self
+    }
+
+    fn lane_budget(&self, lane: GnsLaneId) -> Option<&SendBudget> {
+        self.lanes.iter().find(|(l, _)| *l == lane).map(|(_, b)| b)
+    }
+}
+
+/// A queue found over budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backpressure {
+    /// Lane over budget, `None` for the connection as a whole.
+    pub lane: Option<GnsLaneId>,
+    /// Time a message queued now would wait.
+    pub queue_time: Duration,
+    /// Bytes already queued, reliable and unreliable.
+    pub pending_bytes: u32,
+}
+
+/// Outcome of [`GnsSocket::send_with_budget`].
+#[must_use = "Deferred owns a message to send again later"]
+pub enum BudgetedSend {
+    Sent(GnsMessageNumber),
+    /// The reliable message was over budget and is handed back, to send
+    /// again once the queue drained.
+    Deferred(GnsNetworkMessage<ToSend>, Backpressure),
+    /// The unreliable message was over budget and released.
+    Dropped(Backpressure),
+}
+
+impl<S> GnsSocket<S>
+where
+    S: IsReady,
+{
+    /// Queue `message` unless the queue of its connection is over `budget`,
+    /// see [`GnsSocket::send_with_budget`].
+    ///
+    /// # Errors
+    /// Same as [`GnsSocket::send_with_budget`].
+    #[inline]
+    pub fn try_send(
+        &self,
+        message: GnsNetworkMessage<ToSend>,
+        budget: SendBudget,
+    ) -> GnsResult<BudgetedSend> {
+        self.send_with_budget(message, &SendBudgets::new().connection(budget))
+    }
+
+    /// Queue `message` unless the queue of its connection, or of its lane,
+    /// is over `budgets`. A reliable message over budget is deferred, an
+    /// unreliable one dropped. Resets the jitter high water mark like
+    /// [`GnsSocket::backpressure`].
+    ///
+    /// # Errors
+    /// Same as [`GnsSocket::backpressure`] and [`GnsSocket::send_message`].
+    pub fn send_with_budget(
+        &self,
+        message: GnsNetworkMessage<ToSend>,
+        budgets: &SendBudgets,
+    ) -> GnsResult<BudgetedSend> {
+        let size = u32::try_from(message.payload().len()).unwrap_or(u32::MAX);
+        match self.backpressure(message.connection(), message.lane(), size, budgets)? {
+            None => self.send_message(message).map(BudgetedSend::Sent),
+            Some(backpressure) if message.flags().contains(SendFlags::RELIABLE) => {
+                Ok(BudgetedSend::Deferred(message, backpressure))
+            }
+            Some(backpressure) => Ok(BudgetedSend::Dropped(backpressure)),
+        }
+    }
+
+    /// Backpressure on a message of `size` bytes sent to `lane` of
+    /// `connection`, `None` if it is within `budgets`. The lane is checked
+    /// first.
+    ///
+    /// Unless `budgets` is empty, this reads the real-time status, which
+    /// resets the jitter high water mark of GNS, see
+    /// [`GnsSocket::get_connection_real_time_status`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] if GNS has no status for the connection,
+    /// e.g. `k_EResultNoConnection`, or for the lane, when `lane` has a
+    /// budget but is not configured.
+    pub fn backpressure(
+        &self,
+        connection: GnsConnection,
+        lane: GnsLaneId,
+        size: u32,
+        budgets: &SendBudgets,
+    ) -> GnsResult<Option<Backpressure>> {
+        let lane_budget = budgets.lane_budget(lane);
+        if budgets.connection.is_none() && lane_budget.is_none() {
+            return Ok(None);
+        }
+        let nb_of_lanes = lane_budget.map_or(0, |_| u32::from(lane) + 1);
+        let (status, lanes) = self.get_connection_real_time_status(connection, nb_of_lanes)?;
+        if let Some(budget) = lane_budget {
+            let status = lanes
+                .last()
+                .ok_or(GnsError::Api(EResult::k_EResultInvalidParam))?;
+            let pending_bytes = status.pending_bytes_reliable() + status.pending_bytes_unreliable();
+            if budget.exceeded(status.approximated_queue_time(), pending_bytes, size) {
+                return Ok(Some(Backpressure {
+                    lane: Some(lane),
+                    queue_time: status.approximated_queue_time(),
+                    pending_bytes,
+                }));
+            }
+        }
+        let pending_bytes = status.pending_bytes_reliable() + status.pending_bytes_unreliable();
+        Ok(budgets
+            .connection
+            .filter(|budget| budget.exceeded(status.approximated_queue_time(), pending_bytes, size))
+            .map(|_| Backpressure {
+                lane: None,
+                queue_time: status.approximated_queue_time(),
+                pending_bytes,
+            }))
+    }
+}
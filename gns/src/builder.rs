@@ -0,0 +1,131 @@
+//! Creation of sockets with configuration options applied atomically.
+//!
+//! Values set with [`GnsUtils::set_config`](crate::GnsUtils::set_config)
+//! once a connection exists may race with its handshake. Options given to
+//! [`GnsSocketBuilder`] are passed to GNS along with the creation call
+//! instead, so they are in effect from the first packet.
+
+use crate::config::{ConfigKey, ConfigOption, ConfigValue};
+use crate::{
+    EndReason, GnsError, GnsGlobal, GnsIdentity, GnsResult, GnsSocket, IsClient, IsCreated,
+    IsServer,
+};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+
+/// Builder of a [`GnsSocket`] with configuration options, e.g.
+///
+/// ```no_run
+/// # use gns::{config, GnsGlobal, GnsSocketBuilder};
+/// # use std::net::Ipv4Addr;
+/// let gns_global = GnsGlobal::get().unwrap();
+/// let server = GnsSocketBuilder::new(gns_global)
+///     .option(config::TIMEOUT_CONNECTED, 5_000)
+///     .option(config::IP_ALLOW_WITHOUT_AUTH, 2)
+///     .listen(Ipv4Addr::UNSPECIFIED.into(), 27015)
+///     .unwrap();
+/// ```
+///
+/// Options of a listen socket are inherited by the connections it accepts,
+/// and apply to the listeners added later with
+/// [`GnsSocket::add_listener`](crate::GnsSocket::add_listener) too.
+pub struct GnsSocketBuilder {
+    global: &'static GnsGlobal,
+    options: Vec<ConfigOption>,
+    /// First invalid option, reported on creation.
+    error: Option<GnsError>,
+}
+
+impl GnsSocketBuilder {
+    #[inline]
+    pub fn new(global: &'static GnsGlobal) -> Self {
+        GnsSocketBuilder {
+            global,
+            options: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Apply `key` on creation. Options are applied in order, so a key given
+    /// twice keeps its last value. The connection status callback of the
+    /// wrapper always takes precedence.
+    pub fn option<T: ConfigValue>(mut self, key: ConfigKey<T>, value: impl Into<T>) -> Self {
+        match ConfigOption::new(key, value.into()) {
+            Ok(option) => self.options.push(option),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    fn into_parts(self) -> GnsResult<(GnsSocket<IsCreated>, Vec<ConfigOption>)> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok((GnsSocket::new(self.global), self.options)),
+        }
+    }
+
+    /// [`GnsSocket::listen`] with the options.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if an option is invalid, e.g. a string
+    /// with an interior NUL, and [`GnsError::Listen`] if GNS rejects one.
+    #[inline]
+    pub fn listen(self, address: IpAddr, port: u16) -> GnsResult<GnsSocket<IsServer>> {
+        self.listen_on(SocketAddr::new(address, port))
+    }
+
+    /// [`GnsSocket::listen_on`] with the options.
+    ///
+    /// # Errors
+    /// See [`GnsSocketBuilder::listen`].
+    pub fn listen_on(self, addresses: impl ToSocketAddrs) -> GnsResult<GnsSocket<IsServer>> {
+        let (socket, options) = self.into_parts()?;
+        socket.listen_inner(addresses, options)
+    }
+
+    /// [`GnsSocket::connect`] with the options.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if an option is invalid, e.g. a string
+    /// with an interior NUL, and [`GnsError::Connect`] if GNS rejects one.
+    #[inline]
+    pub fn connect(self, address: IpAddr, port: u16) -> GnsResult<GnsSocket<IsClient>> {
+        self.connect_to(SocketAddr::new(address, port))
+    }
+
+    /// [`GnsSocket::connect_to`] with the options.
+    ///
+    /// # Errors
+    /// See [`GnsSocketBuilder::connect`].
+    pub fn connect_to(self, addresses: impl ToSocketAddrs) -> GnsResult<GnsSocket<IsClient>> {
+        let (socket, options) = self.into_parts()?;
+        socket.connect_inner(addresses, None, &options)
+    }
+
+    /// [`GnsSocket::connect_to_identity`] with the options.
+    ///
+    /// # Errors
+    /// See [`GnsSocketBuilder::connect`].
+    pub fn connect_to_identity(
+        self,
+        addresses: impl ToSocketAddrs,
+        identity: GnsIdentity,
+        reason: EndReason,
+    ) -> GnsResult<GnsSocket<IsClient>> {
+        let (socket, options) = self.into_parts()?;
+        socket.connect_inner(addresses, Some((identity, reason)), &options)
+    }
+
+    /// [`GnsSocket::peer`] with the options, which apply to the connections
+    /// started with [`GnsSocket::connect_p2p`] as well.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if an option is invalid, e.g. a string
+    /// with an interior NUL, and [`GnsError::Listen`] if GNS rejects one.
+    #[cfg(feature = "p2p")]
+    pub fn peer(self, local_virtual_port: i32) -> GnsResult<GnsSocket<crate::IsPeer>> {
+        let (socket, options) = self.into_parts()?;
+        socket.peer_inner(local_virtual_port, options)
+    }
+}
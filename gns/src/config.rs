@@ -0,0 +1,570 @@
+//! Typed, scope-aware access to the GNS configuration values.
+//!
+//! Each [`ConfigKey`] is bound to the Rust type of its value, so a key can
+//! only be set and read with the type GNS stores it as. Values are set on a
+//! [`ConfigScope`]; a value not set on a scope is inherited from the next
+//! broader one (connection, listen socket, then global).
+
+use crate::sys::*;
+use crate::{get_utils, GnsConnection, GnsError, GnsListenSocket, GnsResult, GnsUtils};
+use std::{
+    ffi::{c_void, CStr, CString},
+    iter::FusedIterator,
+    marker::PhantomData,
+};
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+    impl Sealed for f32 {}
+    impl Sealed for String {}
+}
+
+/// Rust type a configuration value is stored as.
+pub trait ConfigValue: private::Sealed + Sized {
+    #[doc(hidden)]
+    const DATA_TYPE: ESteamNetworkingConfigDataType;
+
+    /// Call `f` with the argument `SetConfigValue` expects for `self`.
+    #[doc(hidden)]
+    fn with_arg<R>(&self, f: impl FnOnce(*const c_void) -> R) -> GnsResult<R>;
+
+    #[doc(hidden)]
+    fn from_value(value: GnsConfigValue) -> Option<Self>;
+
+    #[doc(hidden)]
+    fn into_value(self) -> GnsConfigValue;
+}
+
+macro_rules! impl_fixed_config_value {
+    ($($ty:ty => $variant:ident, $data_type:ident;)*) => {$(
+        impl ConfigValue for $ty {
+            const DATA_TYPE: ESteamNetworkingConfigDataType =
+                ESteamNetworkingConfigDataType::$data_type;
+
+            fn with_arg<R>(&self, f: impl FnOnce(*const c_void) -> R) -> GnsResult<R> {
+                Ok(f(self as *const $ty as *const c_void))
+            }
+
+            fn from_value(value: GnsConfigValue) -> Option<Self> {
+                match value {
+                    GnsConfigValue::$variant(x) => Some(x),
+                    _ => None,
+                }
+            }
+
+            fn into_value(self) -> GnsConfigValue {
+                GnsConfigValue::$variant(self)
+            }
+        }
+    )*};
+}
+
+impl_fixed_config_value! {
+    i32 => Int32, k_ESteamNetworkingConfig_Int32;
+    i64 => Int64, k_ESteamNetworkingConfig_Int64;
+    f32 => Float, k_ESteamNetworkingConfig_Float;
+}
+
+impl ConfigValue for String {
+    const DATA_TYPE: ESteamNetworkingConfigDataType =
+        ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_String;
+
+    fn with_arg<R>(&self, f: impl FnOnce(*const c_void) -> R) -> GnsResult<R> {
+        let c = CString::new(self.as_str()).map_err(|_| GnsError::Config("interior NUL"))?;
+        Ok(f(c.as_ptr() as *const c_void))
+    }
+
+    fn from_value(value: GnsConfigValue) -> Option<Self> {
+        match value {
+            GnsConfigValue::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> GnsConfigValue {
+        GnsConfigValue::String(self)
+    }
+}
+
+/// A configuration value read back from GNS, see [`GnsUtils::get_config_value`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum GnsConfigValue {
+    Int32(i32),
+    Int64(i64),
+    Float(f32),
+    String(String),
+    /// Callbacks; only meaningful to the code that installed them.
+    Ptr(*mut c_void),
+}
+
+/// A configuration value bound to the type it is stored as. The keys GNS
+/// documents are the constants of this module, e.g. [`TIMEOUT_INITIAL`].
+pub struct ConfigKey<T> {
+    value: ESteamNetworkingConfigValue,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ConfigKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ConfigKey<T> {}
+
+impl<T> core::fmt::Debug for ConfigKey<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ConfigKey").field(&self.value).finish()
+    }
+}
+
+impl<T: ConfigValue> ConfigKey<T> {
+    /// Bind `value` to `T`, for keys this module does not list. GNS still
+    /// rejects a `T` that doesn't match the value's data type.
+    pub const fn new(value: ESteamNetworkingConfigValue) -> Self {
+        ConfigKey {
+            value,
+            _type: PhantomData,
+        }
+    }
+
+    /// The raw GNS configuration value.
+    #[inline]
+    pub const fn value(self) -> ESteamNetworkingConfigValue {
+        self.value
+    }
+}
+
+macro_rules! config_keys {
+    ($($(#[$doc:meta])* $name:ident: $ty:ty = $value:ident;)*) => {$(
+        $(#[$doc])*
+        pub const $name: ConfigKey<$ty> =
+            ConfigKey::new(ESteamNetworkingConfigValue::$value);
+    )*};
+}
+
+config_keys! {
+    /// Milliseconds to wait for a connection to be established.
+    TIMEOUT_INITIAL: i32 = k_ESteamNetworkingConfig_TimeoutInitial;
+    /// Milliseconds without a reply before a connection is dropped.
+    TIMEOUT_CONNECTED: i32 = k_ESteamNetworkingConfig_TimeoutConnected;
+    /// Maximum bytes buffered for sending per connection.
+    SEND_BUFFER_SIZE: i32 = k_ESteamNetworkingConfig_SendBufferSize;
+    /// Maximum bytes buffered for receiving per connection.
+    RECV_BUFFER_SIZE: i32 = k_ESteamNetworkingConfig_RecvBufferSize;
+    /// Maximum messages buffered for receiving per connection.
+    RECV_BUFFER_MESSAGES: i32 = k_ESteamNetworkingConfig_RecvBufferMessages;
+    /// Maximum size of a received message.
+    RECV_MAX_MESSAGE_SIZE: i32 = k_ESteamNetworkingConfig_RecvMaxMessageSize;
+    /// Maximum message segments per received packet.
+    RECV_MAX_SEGMENTS_PER_PACKET: i32 = k_ESteamNetworkingConfig_RecvMaxSegmentsPerPacket;
+    /// Initial user data of a connection, see
+    /// [`GnsSocket::set_connection_user_data`](crate::GnsSocket::set_connection_user_data).
+    CONNECTION_USER_DATA: i64 = k_ESteamNetworkingConfig_ConnectionUserData;
+    /// Minimum send rate in bytes per second.
+    SEND_RATE_MIN: i32 = k_ESteamNetworkingConfig_SendRateMin;
+    /// Maximum send rate in bytes per second.
+    SEND_RATE_MAX: i32 = k_ESteamNetworkingConfig_SendRateMax;
+    /// Microseconds a message is held to be coalesced with the next ones.
+    NAGLE_TIME: i32 = k_ESteamNetworkingConfig_NagleTime;
+    /// Accept peers without a certificate: 0 never, 1 with a warning, 2 always.
+    IP_ALLOW_WITHOUT_AUTH: i32 = k_ESteamNetworkingConfig_IP_AllowWithoutAuth;
+    /// [`IP_ALLOW_WITHOUT_AUTH`] for loopback addresses.
+    IP_LOCALHOST_ALLOW_WITHOUT_AUTH: i32 = k_ESteamNetworkingConfig_IPLocalHost_AllowWithoutAuth;
+    /// Maximum size of a UDP packet.
+    MTU_PACKET_SIZE: i32 = k_ESteamNetworkingConfig_MTU_PacketSize;
+    /// Maximum payload of a UDP packet, derived from [`MTU_PACKET_SIZE`].
+    MTU_DATA_SIZE: i32 = k_ESteamNetworkingConfig_MTU_DataSize;
+    /// Allow (1) or require (2) unencrypted connections.
+    UNENCRYPTED: i32 = k_ESteamNetworkingConfig_Unencrypted;
+    /// Merge simultaneous connection attempts between two peers.
+    SYMMETRIC_CONNECT: i32 = k_ESteamNetworkingConfig_SymmetricConnect;
+    /// Virtual port of a P2P listen socket or connection.
+    LOCAL_VIRTUAL_PORT: i32 = k_ESteamNetworkingConfig_LocalVirtualPort;
+    /// Send over a second network interface as well.
+    DUAL_WIFI_ENABLE: i32 = k_ESteamNetworkingConfig_DualWifi_Enable;
+    /// Show the Steam client diagnostics UI.
+    ENABLE_DIAGNOSTICS_UI: i32 = k_ESteamNetworkingConfig_EnableDiagnosticsUI;
+    /// Put the time since the previous packet in each packet.
+    SEND_TIME_SINCE_PREVIOUS_PACKET: i32 = k_ESteamNetworkingConfig_SendTimeSincePreviousPacket;
+    /// Percentage of sent packets to drop.
+    FAKE_PACKET_LOSS_SEND: f32 = k_ESteamNetworkingConfig_FakePacketLoss_Send;
+    /// Percentage of received packets to drop.
+    FAKE_PACKET_LOSS_RECV: f32 = k_ESteamNetworkingConfig_FakePacketLoss_Recv;
+    /// Milliseconds of lag added to sent packets.
+    FAKE_PACKET_LAG_SEND: i32 = k_ESteamNetworkingConfig_FakePacketLag_Send;
+    /// Milliseconds of lag added to received packets.
+    FAKE_PACKET_LAG_RECV: i32 = k_ESteamNetworkingConfig_FakePacketLag_Recv;
+    /// Average milliseconds of jitter added to sent packets.
+    FAKE_PACKET_JITTER_SEND_AVG: i32 = k_ESteamNetworkingConfig_FakePacketJitter_Send_Avg;
+    /// Maximum milliseconds of jitter added to sent packets.
+    FAKE_PACKET_JITTER_SEND_MAX: i32 = k_ESteamNetworkingConfig_FakePacketJitter_Send_Max;
+    /// Percentage of sent packets jittered.
+    FAKE_PACKET_JITTER_SEND_PCT: f32 = k_ESteamNetworkingConfig_FakePacketJitter_Send_Pct;
+    /// Average milliseconds of jitter added to received packets.
+    FAKE_PACKET_JITTER_RECV_AVG: i32 = k_ESteamNetworkingConfig_FakePacketJitter_Recv_Avg;
+    /// Maximum milliseconds of jitter added to received packets.
+    FAKE_PACKET_JITTER_RECV_MAX: i32 = k_ESteamNetworkingConfig_FakePacketJitter_Recv_Max;
+    /// Percentage of received packets jittered.
+    FAKE_PACKET_JITTER_RECV_PCT: f32 = k_ESteamNetworkingConfig_FakePacketJitter_Recv_Pct;
+    /// Percentage of sent packets reordered.
+    FAKE_PACKET_REORDER_SEND: f32 = k_ESteamNetworkingConfig_FakePacketReorder_Send;
+    /// Percentage of received packets reordered.
+    FAKE_PACKET_REORDER_RECV: f32 = k_ESteamNetworkingConfig_FakePacketReorder_Recv;
+    /// Milliseconds a reordered packet is delayed.
+    FAKE_PACKET_REORDER_TIME: i32 = k_ESteamNetworkingConfig_FakePacketReorder_Time;
+    /// Percentage of sent packets duplicated.
+    FAKE_PACKET_DUP_SEND: f32 = k_ESteamNetworkingConfig_FakePacketDup_Send;
+    /// Percentage of received packets duplicated.
+    FAKE_PACKET_DUP_RECV: f32 = k_ESteamNetworkingConfig_FakePacketDup_Recv;
+    /// Maximum milliseconds a duplicated packet is delayed.
+    FAKE_PACKET_DUP_TIME_MAX: i32 = k_ESteamNetworkingConfig_FakePacketDup_TimeMax;
+    /// Bytes of each packet written to the packet trace, -1 to disable.
+    PACKET_TRACE_MAX_BYTES: i32 = k_ESteamNetworkingConfig_PacketTraceMaxBytes;
+    /// Simulated outbound bandwidth in bytes per second.
+    FAKE_RATE_LIMIT_SEND_RATE: i32 = k_ESteamNetworkingConfig_FakeRateLimit_Send_Rate;
+    /// Simulated outbound burst in bytes.
+    FAKE_RATE_LIMIT_SEND_BURST: i32 = k_ESteamNetworkingConfig_FakeRateLimit_Send_Burst;
+    /// Simulated inbound bandwidth in bytes per second.
+    FAKE_RATE_LIMIT_RECV_RATE: i32 = k_ESteamNetworkingConfig_FakeRateLimit_Recv_Rate;
+    /// Simulated inbound burst in bytes.
+    FAKE_RATE_LIMIT_RECV_BURST: i32 = k_ESteamNetworkingConfig_FakeRateLimit_Recv_Burst;
+    /// Microseconds to wait for out of order packets.
+    OUT_OF_ORDER_CORRECTION_WINDOW_MICROSECONDS: i32 =
+        k_ESteamNetworkingConfig_OutOfOrderCorrectionWindowMicroseconds;
+    /// Comma separated `host:port` STUN servers.
+    P2P_STUN_SERVER_LIST: String = k_ESteamNetworkingConfig_P2P_STUN_ServerList;
+    /// Which ICE candidates to use, a `k_nSteamNetworkingConfig_P2P_Transport_ICE_Enable_*` mask.
+    P2P_TRANSPORT_ICE_ENABLE: i32 = k_ESteamNetworkingConfig_P2P_Transport_ICE_Enable;
+    /// Milliseconds of penalty on ICE routes.
+    P2P_TRANSPORT_ICE_PENALTY: i32 = k_ESteamNetworkingConfig_P2P_Transport_ICE_Penalty;
+    /// Milliseconds of penalty on relayed routes.
+    P2P_TRANSPORT_SDR_PENALTY: i32 = k_ESteamNetworkingConfig_P2P_Transport_SDR_Penalty;
+    /// Comma separated TURN servers.
+    P2P_TURN_SERVER_LIST: String = k_ESteamNetworkingConfig_P2P_TURN_ServerList;
+    /// Comma separated TURN user names, one per server.
+    P2P_TURN_USER_LIST: String = k_ESteamNetworkingConfig_P2P_TURN_UserList;
+    /// Comma separated TURN passwords, one per server.
+    P2P_TURN_PASS_LIST: String = k_ESteamNetworkingConfig_P2P_TURN_PassList;
+    /// ICE implementation to use.
+    P2P_TRANSPORT_ICE_IMPLEMENTATION: i32 = k_ESteamNetworkingConfig_P2P_Transport_ICE_Implementation;
+    /// Log level of RTT calculations.
+    LOG_LEVEL_ACK_RTT: i32 = k_ESteamNetworkingConfig_LogLevel_AckRTT;
+    /// Log level of packet decoding.
+    LOG_LEVEL_PACKET_DECODE: i32 = k_ESteamNetworkingConfig_LogLevel_PacketDecode;
+    /// Log level of each message sent and received.
+    LOG_LEVEL_MESSAGE: i32 = k_ESteamNetworkingConfig_LogLevel_Message;
+    /// Log level of dropped packets.
+    LOG_LEVEL_PACKET_GAPS: i32 = k_ESteamNetworkingConfig_LogLevel_PacketGaps;
+    /// Log level of P2P rendezvous.
+    LOG_LEVEL_P2P_RENDEZVOUS: i32 = k_ESteamNetworkingConfig_LogLevel_P2PRendezvous;
+    /// Log level of relay pings.
+    LOG_LEVEL_SDR_RELAY_PINGS: i32 = k_ESteamNetworkingConfig_LogLevel_SDRRelayPings;
+}
+
+/// A value applied when a socket or connection is created, see
+/// [`GnsSocketBuilder`](crate::GnsSocketBuilder).
+#[derive(Clone, Debug)]
+pub(crate) struct ConfigOption {
+    value: ESteamNetworkingConfigValue,
+    data: OptionData,
+}
+
+#[derive(Clone, Debug)]
+enum OptionData {
+    Int32(i32),
+    Int64(i64),
+    Float(f32),
+    String(CString),
+}
+
+impl ConfigOption {
+    pub(crate) fn new<T: ConfigValue>(key: ConfigKey<T>, value: T) -> GnsResult<Self> {
+        let data = match value.into_value() {
+            GnsConfigValue::Int32(x) => OptionData::Int32(x),
+            GnsConfigValue::Int64(x) => OptionData::Int64(x),
+            GnsConfigValue::Float(x) => OptionData::Float(x),
+            GnsConfigValue::String(x) => {
+                OptionData::String(CString::new(x).map_err(|_| GnsError::Config("interior NUL"))?)
+            }
+            GnsConfigValue::Ptr(_) => unreachable!("no ConfigValue is a pointer"),
+        };
+        Ok(ConfigOption {
+            value: key.value,
+            data,
+        })
+    }
+
+    /// The option as GNS takes it. A string is borrowed from `self`, which
+    /// must outlive the call it is passed to.
+    pub(crate) fn raw(&self) -> SteamNetworkingConfigValue_t {
+        let (data_type, val) = match &self.data {
+            OptionData::Int32(x) => (
+                ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int32,
+                SteamNetworkingConfigValue_t__bindgen_ty_1 { m_int32: *x },
+            ),
+            OptionData::Int64(x) => (
+                ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int64,
+                SteamNetworkingConfigValue_t__bindgen_ty_1 { m_int64: *x },
+            ),
+            OptionData::Float(x) => (
+                ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Float,
+                SteamNetworkingConfigValue_t__bindgen_ty_1 { m_float: *x },
+            ),
+            OptionData::String(x) => (
+                ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_String,
+                SteamNetworkingConfigValue_t__bindgen_ty_1 {
+                    m_string: x.as_ptr(),
+                },
+            ),
+        };
+        SteamNetworkingConfigValue_t {
+            m_eValue: self.value,
+            m_eDataType: data_type,
+            m_val: val,
+        }
+    }
+}
+
+/// The object a configuration value is set on. Connections inherit from
+/// their listen socket, which inherits from the global scope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigScope {
+    Global,
+    ListenSocket(GnsListenSocket),
+    Connection(GnsConnection),
+}
+
+impl ConfigScope {
+    fn raw(self) -> (ESteamNetworkingConfigScope, isize) {
+        match self {
+            ConfigScope::Global => (
+                ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_Global,
+                0,
+            ),
+            ConfigScope::ListenSocket(socket) => (
+                ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_ListenSocket,
+                socket.0 as isize,
+            ),
+            ConfigScope::Connection(conn) => (
+                ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_Connection,
+                conn.0 as isize,
+            ),
+        }
+    }
+}
+
+impl From<GnsListenSocket> for ConfigScope {
+    fn from(socket: GnsListenSocket) -> Self {
+        ConfigScope::ListenSocket(socket)
+    }
+}
+
+impl From<GnsConnection> for ConfigScope {
+    fn from(conn: GnsConnection) -> Self {
+        ConfigScope::Connection(conn)
+    }
+}
+
+/// Description of a configuration value, see [`GnsUtils::config_values`].
+/// GNS exposes no human-readable description of the values, only their
+/// name; what each one does is documented in its headers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConfigValueInfo {
+    pub value: ESteamNetworkingConfigValue,
+    pub name: &'static str,
+    pub data_type: ESteamNetworkingConfigDataType,
+    /// Narrowest scope the value can be set on.
+    pub scope: ESteamNetworkingConfigScope,
+}
+
+impl GnsUtils {
+    /// Set `key` on `scope`.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if GNS rejects the value, e.g. for a key
+    /// that can't be set on `scope` or a scope object that doesn't exist.
+    pub fn set_config<T: ConfigValue>(
+        &self,
+        scope: impl Into<ConfigScope>,
+        key: ConfigKey<T>,
+        value: impl Into<T>,
+    ) -> GnsResult<()> {
+        let (scope_type, scope_obj) = scope.into().raw();
+        value
+            .into()
+            .with_arg(|arg| unsafe {
+                SteamAPI_ISteamNetworkingUtils_SetConfigValue(
+                    get_utils(),
+                    key.value,
+                    scope_type,
+                    scope_obj,
+                    T::DATA_TYPE,
+                    arg,
+                )
+            })?
+            .then_some(())
+            .ok_or(GnsError::Config("SetConfigValue rejected"))
+    }
+
+    /// Remove `key` from `scope`, which inherits it again.
+    ///
+    /// # Errors
+    /// Same as [`GnsUtils::set_config`].
+    pub fn reset_config<T: ConfigValue>(
+        &self,
+        scope: impl Into<ConfigScope>,
+        key: ConfigKey<T>,
+    ) -> GnsResult<()> {
+        let (scope_type, scope_obj) = scope.into().raw();
+        unsafe {
+            SteamAPI_ISteamNetworkingUtils_SetConfigValue(
+                get_utils(),
+                key.value,
+                scope_type,
+                scope_obj,
+                T::DATA_TYPE,
+                core::ptr::null(),
+            )
+        }
+        .then_some(())
+        .ok_or(GnsError::Config("SetConfigValue rejected"))
+    }
+
+    /// Effective value of `key` on `scope`, inherited or not.
+    ///
+    /// # Errors
+    /// See [`GnsUtils::get_config_value`].
+    pub fn get_config<T: ConfigValue>(
+        &self,
+        scope: impl Into<ConfigScope>,
+        key: ConfigKey<T>,
+    ) -> GnsResult<T> {
+        let (value, _) = self.get_config_value(scope, key.value)?;
+        T::from_value(value).ok_or(GnsError::Config("config value type mismatch"))
+    }
+
+    /// Effective value of the untyped `value` on `scope`, and whether it is
+    /// inherited from a broader scope rather than set on this one.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if `value` is unknown or the scope object
+    /// doesn't exist.
+    pub fn get_config_value(
+        &self,
+        scope: impl Into<ConfigScope>,
+        value: ESteamNetworkingConfigValue,
+    ) -> GnsResult<(GnsConfigValue, bool)> {
+        let (scope_type, scope_obj) = scope.into().raw();
+        let get = |data_type: &mut ESteamNetworkingConfigDataType,
+                   buf: *mut c_void,
+                   len: &mut usize| unsafe {
+            SteamAPI_ISteamNetworkingUtils_GetConfigValue(
+                get_utils(),
+                value,
+                scope_type,
+                scope_obj,
+                data_type,
+                buf,
+                len,
+            )
+        };
+
+        // Large enough for every fixed size type; strings report their size.
+        let mut data_type = ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int32;
+        let mut fixed = [0u8; 8];
+        let mut len = fixed.len();
+        let mut result = get(&mut data_type, fixed.as_mut_ptr() as _, &mut len);
+        let mut string = Vec::new();
+        if data_type == ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_String {
+            string.resize(len.max(1), 0u8);
+            len = string.len();
+            result = get(&mut data_type, string.as_mut_ptr() as _, &mut len);
+        }
+
+        let inherited = match result {
+            ESteamNetworkingGetConfigValueResult::k_ESteamNetworkingGetConfigValue_OK => false,
+            ESteamNetworkingGetConfigValueResult::k_ESteamNetworkingGetConfigValue_OKInherited => {
+                true
+            }
+            ESteamNetworkingGetConfigValueResult::k_ESteamNetworkingGetConfigValue_BadScopeObj => {
+                return Err(GnsError::Config("no such config scope object"))
+            }
+            _ => return Err(GnsError::Config("GetConfigValue failed")),
+        };
+        let config = match data_type {
+            ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int32 => {
+                GnsConfigValue::Int32(i32::from_ne_bytes(fixed[..4].try_into().unwrap()))
+            }
+            ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int64 => {
+                GnsConfigValue::Int64(i64::from_ne_bytes(fixed))
+            }
+            ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Float => {
+                GnsConfigValue::Float(f32::from_ne_bytes(fixed[..4].try_into().unwrap()))
+            }
+            ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_String => {
+                let s = CStr::from_bytes_until_nul(&string)
+                    .map_err(|_| GnsError::Config("unterminated config string"))?;
+                GnsConfigValue::String(s.to_string_lossy().into_owned())
+            }
+            ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Ptr => GnsConfigValue::Ptr(
+                usize::from_ne_bytes(fixed[..core::mem::size_of::<usize>()].try_into().unwrap())
+                    as *mut c_void,
+            ),
+            _ => return Err(GnsError::Config("unknown config data type")),
+        };
+        Ok((config, inherited))
+    }
+
+    /// Name, data type and scope of `value`, `None` if GNS doesn't know it.
+    pub fn config_value_info(&self, value: ESteamNetworkingConfigValue) -> Option<ConfigValueInfo> {
+        let mut data_type = ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int32;
+        let mut scope = ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_Global;
+        let name = unsafe {
+            SteamAPI_ISteamNetworkingUtils_GetConfigValueInfo(
+                get_utils(),
+                value,
+                &mut data_type,
+                &mut scope,
+            )
+        };
+        if name.is_null() {
+            return None;
+        }
+        // Names live in the static table of GNS.
+        let name = unsafe { CStr::from_ptr(name) }.to_str().ok()?;
+        Some(ConfigValueInfo {
+            value,
+            name,
+            data_type,
+            scope,
+        })
+    }
+
+    /// Every configuration value that can be edited, such as through
+    /// [`GnsUtils::get_config_value`]. Developer values, mostly for testing
+    /// GNS itself, are only listed with `dev_values`. Once exhausted, the
+    /// iterator keeps returning `None`.
+    pub fn config_values(
+        &self,
+        dev_values: bool,
+    ) -> impl FusedIterator<Item = ConfigValueInfo> + '_ {
+        let mut current = ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Invalid;
+        core::iter::from_fn(move || {
+            current = unsafe {
+                SteamAPI_ISteamNetworkingUtils_IterateGenericEditableConfigValues(
+                    get_utils(),
+                    current,
+                    dev_values,
+                )
+            };
+            (current != ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Invalid)
+                .then_some(current)
+        })
+        // GNS starts over from the first value after the last one.
+        .fuse()
+        .filter_map(|value| self.config_value_info(value))
+    }
+}
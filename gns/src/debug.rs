@@ -0,0 +1,262 @@
+//! Debug output of GNS, forwarded to a callback or, with the `tracing` and
+//! `log` features, to the logging facade of the application.
+//!
+//! GNS holds a single output function and detail level for the whole
+//! process. The wrapper installs a trampoline as that function and forwards
+//! to the sinks in place, which can be replaced or removed at any time.
+
+use crate::sys::*;
+use crate::{get_utils, GnsUtils};
+use std::{
+    ffi::CStr,
+    sync::{Arc, Mutex, RwLock},
+};
+
+type MsgPtr = *const ::std::os::raw::c_char;
+
+/// User-supplied debug callback. `Send + Sync` because it is invoked from the
+/// GNS service thread, and may capture state shared with the caller's threads.
+type DebugCallback = dyn Fn(ESteamNetworkingSocketsDebugOutputType, &str) + Send + Sync + 'static;
+
+/// Packet trace in progress, see
+/// [`GnsDiagnostics::trace_packets`](crate::GnsDiagnostics::trace_packets).
+pub(crate) struct PacketTrace {
+    /// Identifier of the traced connection in the output of GNS, the `#1234`
+    /// its description starts with.
+    pub(crate) connection: String,
+    pub(crate) sink: Box<dyn Fn(&str) + Send + Sync + 'static>,
+}
+
+impl PacketTrace {
+    /// Whether `line` is about the traced connection, i.e. mentions its
+    /// identifier and not one merely starting with the same digits.
+    fn traces(&self, line: &str) -> bool {
+        line.match_indices(&self.connection)
+            .any(|(at, id)| !line[at + id.len()..].starts_with(|c: char| c.is_ascii_digit()))
+    }
+}
+
+/// Sinks of the debug output.
+struct DebugOutput {
+    callback: Option<Arc<DebugCallback>>,
+    /// Detail level of `callback`.
+    level: ESteamNetworkingSocketsDebugOutputType,
+    packet_trace: Option<Arc<PacketTrace>>,
+}
+
+impl DebugOutput {
+    /// Detail level GNS must produce output at: a packet trace needs every
+    /// line, the callback only gets the ones up to its own level.
+    fn gns_level(&self) -> ESteamNetworkingSocketsDebugOutputType {
+        use ESteamNetworkingSocketsDebugOutputType::*;
+        match (&self.callback, &self.packet_trace) {
+            (_, Some(_)) => k_ESteamNetworkingSocketsDebugOutputType_Everything,
+            (Some(_), None) => self.level,
+            (None, None) => k_ESteamNetworkingSocketsDebugOutputType_None,
+        }
+    }
+}
+
+/// Replaced via [`GnsUtils::enable_debug_output`]; read from the GNS service
+/// thread. The trampoline clones the sinks out of the lock, so a callback may
+/// itself replace them.
+static DEBUG_OUTPUT: RwLock<DebugOutput> = RwLock::new(DebugOutput {
+    callback: None,
+    level: ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_None,
+    packet_trace: None,
+});
+
+/// Prefix of the packet trace lines of GNS.
+const PACKET_TRACE_PREFIX: &str = "[Trace ";
+
+unsafe extern "C" fn debug_trampoline(ty: ESteamNetworkingSocketsDebugOutputType, msg: MsgPtr) {
+    let (cb, level, packet_trace) = {
+        let output = DEBUG_OUTPUT.read().unwrap_or_else(|e| e.into_inner());
+        (
+            output.callback.clone(),
+            output.level,
+            output.packet_trace.clone(),
+        )
+    };
+    let s = unsafe { CStr::from_ptr(msg) }.to_str().unwrap_or("");
+    if let Some(trace) = packet_trace {
+        if s.starts_with(PACKET_TRACE_PREFIX) {
+            // Tracing is global to GNS, drop the packets of other connections.
+            if trace.traces(s) {
+                (trace.sink)(s);
+            }
+            return;
+        }
+    }
+    if let Some(cb) = cb.filter(|_| ty <= level) {
+        cb(ty, s);
+    }
+}
+
+/// Change the sinks, then the detail level of GNS accordingly.
+fn update_debug_output(f: impl FnOnce(&mut DebugOutput)) {
+    // Keeps concurrent updates from handing GNS their levels out of order,
+    // without holding `DEBUG_OUTPUT` over the call.
+    static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let level = {
+        let mut output = DEBUG_OUTPUT.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut output);
+        output.gns_level()
+    };
+    let trampoline = (level
+        != ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_None)
+        .then_some(debug_trampoline as unsafe extern "C" fn(_, _));
+    unsafe { SteamAPI_ISteamNetworkingUtils_SetDebugOutputFunction(get_utils(), level, trampoline) }
+}
+
+/// Start or stop, with `None`, the packet trace.
+pub(crate) fn set_packet_trace(trace: Option<PacketTrace>) {
+    update_debug_output(|output| output.packet_trace = trace.map(Arc::new));
+}
+
+/// A line of debug output, split into the connection it is about and the
+/// message itself. GNS prefixes the lines about a connection with its
+/// description in brackets, e.g. `[#2715 UDP 127.0.0.1:27015 'player'] ...`,
+/// where the quoted part is the name given with
+/// [`GnsSocket::set_connection_name`](crate::GnsSocket::set_connection_name).
+#[cfg(any(feature = "tracing", feature = "log"))]
+struct DebugLine<'a> {
+    connection: Option<&'a str>,
+    name: Option<&'a str>,
+    message: &'a str,
+}
+
+#[cfg(any(feature = "tracing", feature = "log"))]
+impl<'a> DebugLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        let line = line.trim_end();
+        let Some((connection, message)) = line
+            .strip_prefix("[#")
+            .and_then(|rest| rest.split_once("] "))
+        else {
+            return DebugLine {
+                connection: None,
+                name: None,
+                message: line,
+            };
+        };
+        let name = connection
+            .strip_suffix('\'')
+            .and_then(|rest| rest.split_once(" '"))
+            .map(|(_, name)| name);
+        DebugLine {
+            connection: Some(&line[1..connection.len() + 2]),
+            name,
+            message,
+        }
+    }
+}
+
+impl GnsUtils {
+    /// Install a debug callback, replacing the current one. The callback runs
+    /// on GNS's service thread; the `&str` is borrowed for the call duration
+    /// only.
+    ///
+    /// The callback may capture state (it is stored as a boxed closure), but
+    /// must therefore be `Send + Sync + 'static` since GNS invokes it from its
+    /// own thread.
+    pub fn enable_debug_output(
+        &self,
+        ty: ESteamNetworkingSocketsDebugOutputType,
+        f: impl Fn(ESteamNetworkingSocketsDebugOutputType, &str) + Send + Sync + 'static,
+    ) {
+        update_debug_output(|output| {
+            output.callback = Some(Arc::new(f));
+            output.level = ty;
+        });
+    }
+
+    /// Change the verbosity of the debug callback at runtime: messages less
+    /// important than `ty` are not even formatted by GNS, unless a packet
+    /// trace is in progress.
+    pub fn set_debug_output_level(&self, ty: ESteamNetworkingSocketsDebugOutputType) {
+        update_debug_output(|output| output.level = ty);
+    }
+
+    /// Remove the debug callback. A packet trace, see
+    /// [`GnsDiagnostics::trace_packets`](crate::GnsDiagnostics::trace_packets),
+    /// goes on.
+    pub fn disable_debug_output(&self) {
+        update_debug_output(|output| output.callback = None);
+    }
+
+    /// Forward the debug output up to `ty` to `tracing`, as events of the
+    /// `gns` target. Bugs and errors are `ERROR`, important messages and
+    /// warnings `WARN`, messages `INFO`, verbose output `DEBUG` and the rest
+    /// `TRACE`. Lines about a connection carry its description in the
+    /// `connection` field, and its name, if any, in `connection_name`.
+    ///
+    /// Replaces the current callback, see [`GnsUtils::enable_debug_output`].
+    #[cfg(feature = "tracing")]
+    pub fn enable_tracing_output(&self, ty: ESteamNetworkingSocketsDebugOutputType) {
+        use ESteamNetworkingSocketsDebugOutputType::*;
+        macro_rules! event {
+            ($level:expr, $line:expr) => {
+                tracing::event!(
+                    target: "gns",
+                    $level,
+                    connection = $line.connection,
+                    connection_name = $line.name,
+                    "{}",
+                    $line.message
+                )
+            };
+        }
+        self.enable_debug_output(ty, |ty, msg| {
+            let line = DebugLine::parse(msg);
+            match ty {
+                k_ESteamNetworkingSocketsDebugOutputType_Bug
+                | k_ESteamNetworkingSocketsDebugOutputType_Error => {
+                    event!(tracing::Level::ERROR, line)
+                }
+                k_ESteamNetworkingSocketsDebugOutputType_Important
+                | k_ESteamNetworkingSocketsDebugOutputType_Warning => {
+                    event!(tracing::Level::WARN, line)
+                }
+                k_ESteamNetworkingSocketsDebugOutputType_Msg => event!(tracing::Level::INFO, line),
+                k_ESteamNetworkingSocketsDebugOutputType_Verbose => {
+                    event!(tracing::Level::DEBUG, line)
+                }
+                _ => event!(tracing::Level::TRACE, line),
+            }
+        });
+    }
+
+    /// Forward the debug output up to `ty` to `log`, as records of the `gns`
+    /// target, with the same levels as
+    /// [`GnsUtils::enable_tracing_output`]. Lines about a connection carry
+    /// its description in the `connection` key, and its name, if any, in
+    /// `connection_name`.
+    ///
+    /// Replaces the current callback, see [`GnsUtils::enable_debug_output`].
+    #[cfg(feature = "log")]
+    pub fn enable_log_output(&self, ty: ESteamNetworkingSocketsDebugOutputType) {
+        use ESteamNetworkingSocketsDebugOutputType::*;
+        self.enable_debug_output(ty, |ty, msg| {
+            let level = match ty {
+                k_ESteamNetworkingSocketsDebugOutputType_Bug
+                | k_ESteamNetworkingSocketsDebugOutputType_Error => log::Level::Error,
+                k_ESteamNetworkingSocketsDebugOutputType_Important
+                | k_ESteamNetworkingSocketsDebugOutputType_Warning => log::Level::Warn,
+                k_ESteamNetworkingSocketsDebugOutputType_Msg => log::Level::Info,
+                k_ESteamNetworkingSocketsDebugOutputType_Verbose => log::Level::Debug,
+                _ => log::Level::Trace,
+            };
+            let line = DebugLine::parse(msg);
+            log::log!(
+                target: "gns",
+                level,
+                connection = line.connection,
+                connection_name = line.name;
+                "{}",
+                line.message
+            );
+        });
+    }
+}
@@ -0,0 +1,225 @@
+//! Per-subsystem log levels and packet tracing.
+//!
+//! Besides the detail level of the debug output, GNS has a log level for
+//! each of its noisier subsystems. A subsystem only reports what is at or
+//! above its own level, and what it reports still has to pass the level of
+//! the debug output, see
+//! [`GnsUtils::enable_debug_output`](crate::GnsUtils::enable_debug_output).
+
+use crate::config::{self, ConfigKey, ConfigScope};
+use crate::debug::{set_packet_trace, PacketTrace};
+use crate::sys::*;
+use crate::{
+    get_interface, GnsConnection, GnsConnectionInfo, GnsError, GnsGlobal, GnsResult, GnsUtils,
+};
+
+/// Detail level of GNS output, from nothing to everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    None,
+    Bug,
+    Error,
+    Important,
+    Warning,
+    Message,
+    Verbose,
+    Debug,
+    Everything,
+}
+
+impl From<LogLevel> for ESteamNetworkingSocketsDebugOutputType {
+    fn from(level: LogLevel) -> Self {
+        use ESteamNetworkingSocketsDebugOutputType::*;
+        match level {
+            LogLevel::None => k_ESteamNetworkingSocketsDebugOutputType_None,
+            LogLevel::Bug => k_ESteamNetworkingSocketsDebugOutputType_Bug,
+            LogLevel::Error => k_ESteamNetworkingSocketsDebugOutputType_Error,
+            LogLevel::Important => k_ESteamNetworkingSocketsDebugOutputType_Important,
+            LogLevel::Warning => k_ESteamNetworkingSocketsDebugOutputType_Warning,
+            LogLevel::Message => k_ESteamNetworkingSocketsDebugOutputType_Msg,
+            LogLevel::Verbose => k_ESteamNetworkingSocketsDebugOutputType_Verbose,
+            LogLevel::Debug => k_ESteamNetworkingSocketsDebugOutputType_Debug,
+            LogLevel::Everything => k_ESteamNetworkingSocketsDebugOutputType_Everything,
+        }
+    }
+}
+
+impl From<LogLevel> for i32 {
+    #[inline]
+    fn from(level: LogLevel) -> Self {
+        ESteamNetworkingSocketsDebugOutputType::from(level) as i32
+    }
+}
+
+impl TryFrom<i32> for LogLevel {
+    type Error = GnsError;
+
+    fn try_from(value: i32) -> GnsResult<Self> {
+        [
+            LogLevel::None,
+            LogLevel::Bug,
+            LogLevel::Error,
+            LogLevel::Important,
+            LogLevel::Warning,
+            LogLevel::Message,
+            LogLevel::Verbose,
+            LogLevel::Debug,
+            LogLevel::Everything,
+        ]
+        .into_iter()
+        .find(|level| i32::from(*level) == value)
+        .ok_or(GnsError::Config("unknown log level"))
+    }
+}
+
+/// Subsystem of GNS with its own log level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSubsystem {
+    /// RTT calculations for inline pings and replies.
+    AckRtt,
+    /// Decoding of the packets received.
+    PacketDecode,
+    /// Each message sent and received.
+    Message,
+    /// Dropped packets.
+    PacketGaps,
+    /// P2P rendezvous messages.
+    P2PRendezvous,
+    /// Pings to the relays.
+    SdrRelayPings,
+}
+
+impl LogSubsystem {
+    /// Every subsystem.
+    pub const ALL: [LogSubsystem; 6] = [
+        LogSubsystem::AckRtt,
+        LogSubsystem::PacketDecode,
+        LogSubsystem::Message,
+        LogSubsystem::PacketGaps,
+        LogSubsystem::P2PRendezvous,
+        LogSubsystem::SdrRelayPings,
+    ];
+
+    /// The configuration key holding the level of the subsystem.
+    pub const fn key(self) -> ConfigKey<i32> {
+        match self {
+            LogSubsystem::AckRtt => config::LOG_LEVEL_ACK_RTT,
+            LogSubsystem::PacketDecode => config::LOG_LEVEL_PACKET_DECODE,
+            LogSubsystem::Message => config::LOG_LEVEL_MESSAGE,
+            LogSubsystem::PacketGaps => config::LOG_LEVEL_PACKET_GAPS,
+            LogSubsystem::P2PRendezvous => config::LOG_LEVEL_P2P_RENDEZVOUS,
+            LogSubsystem::SdrRelayPings => config::LOG_LEVEL_SDR_RELAY_PINGS,
+        }
+    }
+}
+
+/// Diagnostics operations, see [`GnsGlobal::diagnostics`].
+pub struct GnsDiagnostics(pub(crate) &'static GnsUtils);
+
+impl GnsDiagnostics {
+    /// Set the log level of `subsystem` on `scope`, e.g. globally or for the
+    /// connection under investigation.
+    ///
+    /// # Errors
+    /// Same as [`GnsUtils::set_config`](crate::GnsUtils::set_config).
+    pub fn set_log_level(
+        &self,
+        scope: impl Into<ConfigScope>,
+        subsystem: LogSubsystem,
+        level: LogLevel,
+    ) -> GnsResult<()> {
+        self.0.set_config(scope, subsystem.key(), level)
+    }
+
+    /// Set the log level of every subsystem on `scope`.
+    ///
+    /// # Errors
+    /// Same as [`GnsUtils::set_config`](crate::GnsUtils::set_config).
+    pub fn set_log_levels(&self, scope: impl Into<ConfigScope>, level: LogLevel) -> GnsResult<()> {
+        let scope = scope.into();
+        LogSubsystem::ALL
+            .into_iter()
+            .try_for_each(|subsystem| self.set_log_level(scope, subsystem, level))
+    }
+
+    /// Effective log level of `subsystem` on `scope`.
+    ///
+    /// # Errors
+    /// Same as [`GnsUtils::get_config`](crate::GnsUtils::get_config), and
+    /// [`GnsError::Config`] if GNS holds a level this enum doesn't know.
+    pub fn log_level(
+        &self,
+        scope: impl Into<ConfigScope>,
+        subsystem: LogSubsystem,
+    ) -> GnsResult<LogLevel> {
+        self.0
+            .get_config(scope, subsystem.key())
+            .and_then(LogLevel::try_from)
+    }
+
+    /// Trace the packets of `connection` to `sink`, one line per packet with
+    /// up to `max_bytes` of its payload, until
+    /// [`GnsDiagnostics::stop_packet_trace`]. Only connections over UDP are
+    /// traced, and a single one at a time: a new trace replaces the previous
+    /// one.
+    ///
+    /// GNS traces the packets of every connection; the lines not mentioning
+    /// the `#1234` identifier `connection` is described with, see
+    /// [`GnsConnectionInfo::description`], are dropped. None of them reach
+    /// the debug callback, which keeps its level.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultNoConnection` if
+    /// `connection` doesn't exist.
+    pub fn trace_packets(
+        &self,
+        GnsConnection(conn): GnsConnection,
+        max_bytes: u32,
+        sink: impl Fn(&str) + Send + Sync + 'static,
+    ) -> GnsResult<()> {
+        let mut info = SteamNetConnectionInfo_t::default();
+        if !unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetConnectionInfo(get_interface(), conn, &mut info)
+        } {
+            return Err(GnsError::Api(EResult::k_EResultNoConnection));
+        }
+        let info = GnsConnectionInfo(info);
+        let description = info.description();
+        set_packet_trace(Some(PacketTrace {
+            connection: description
+                .split_whitespace()
+                .next()
+                .filter(|id| id.starts_with('#'))
+                .unwrap_or(description)
+                .to_owned(),
+            sink: Box::new(sink),
+        }));
+        self.0
+            .set_config(
+                ConfigScope::Global,
+                config::PACKET_TRACE_MAX_BYTES,
+                max_bytes.min(i32::MAX as u32) as i32,
+            )
+            .inspect_err(|_| set_packet_trace(None))
+    }
+
+    /// Stop the packet trace, if any.
+    ///
+    /// # Errors
+    /// Same as [`GnsUtils::set_config`](crate::GnsUtils::set_config).
+    pub fn stop_packet_trace(&self) -> GnsResult<()> {
+        // -1 disables the trace, 0 would still trace the headers.
+        let result = self
+            .0
+            .set_config(ConfigScope::Global, config::PACKET_TRACE_MAX_BYTES, -1);
+        set_packet_trace(None);
+        result
+    }
+}
+
+impl GnsGlobal {
+    #[inline]
+    pub fn diagnostics(&self) -> &GnsDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -0,0 +1,337 @@
+//! Background callback driver backing the `async` receive API (`tokio` feature).
+//!
+//! A single task per process, spawned lazily on the current tokio runtime by
+//! the first pending future, runs [`GnsGlobal::poll_callbacks`] on a tick and
+//! wakes the tasks waiting on a [`GnsSocket`] that got something. The task
+//! parks on a [`Notify`] whenever nobody is waiting, so idle processes do not
+//! spin, and backs its tick off while the waiting sockets get nothing.
+//! In [manual poll mode](crate::GnsInitOptions::manual_poll) each tick also
+//! runs a non-blocking [`GnsGlobal::poll`].
+//!
+//! GNS does not signal message arrival: the driver takes the oldest pending
+//! message of each socket waiting for one and holds it for the socket, whose
+//! receive functions return it first. Futures never hold a message across a
+//! suspension point, so dropping one cannot lose a message.
+
+use crate::{get_interface, sys::*};
+use crate::{
+    take_message, GnsConnectionEvent, GnsError, GnsGlobal, GnsNetworkMessage, GnsResult, GnsSocket,
+    IsReady, MessageSlot, ToReceive,
+};
+use std::{
+    collections::HashMap,
+    future::poll_fn,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// Default interval between two [`GnsGlobal::poll_callbacks`] runs of the
+/// driver, while waiting sockets get something.
+pub const DEFAULT_DRIVER_TICK: Duration = Duration::from_millis(1);
+
+/// Longest interval of the driver, as a multiple of its tick, reached by
+/// doubling the interval on each tick where no waiting socket got anything.
+const MAX_BACKOFF: u32 = 16;
+
+/// Handle the messages of a socket are received from.
+#[derive(Debug, Clone, Copy)]
+pub enum MessageSource {
+    Connection(HSteamNetConnection),
+    PollGroup(HSteamNetPollGroup),
+}
+
+impl MessageSource {
+    /// Take the oldest pending message, if any.
+    fn take(self) -> GnsResult<Option<GnsNetworkMessage<ToReceive>>> {
+        let mut slots = [MessageSlot::uninit()];
+        let result = unsafe {
+            match self {
+                MessageSource::Connection(connection) => {
+                    SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnConnection(
+                        get_interface(),
+                        connection,
+                        slots.as_mut_ptr() as _,
+                        1,
+                    )
+                }
+                MessageSource::PollGroup(poll_group) => {
+                    SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(
+                        get_interface(),
+                        poll_group,
+                        slots.as_mut_ptr() as _,
+                        1,
+                    )
+                }
+            }
+        };
+        match result {
+            ..=-1 => Err(GnsError::Receive),
+            0 => Ok(None),
+            _ => {
+                #[cfg(feature = "metrics")]
+                crate::sampler::record_received(1);
+                // Safety: GNS initialized the single slot it reported.
+                Ok(Some(unsafe { take_message(&slots[0]) }))
+            }
+        }
+    }
+}
+
+/// Tasks waiting on a socket.
+struct Waiting {
+    source: MessageSource,
+    messages: Vec<Waker>,
+    events: Vec<Waker>,
+}
+
+/// What a task waits for.
+#[derive(Clone, Copy)]
+enum Interest {
+    Message,
+    Event,
+}
+
+struct Driver {
+    /// Tasks waiting for a message or an event, by socket queue id.
+    waiting: Mutex<HashMap<i64, Waiting>>,
+    /// Oldest pending message of the sockets it was taken from, by queue id.
+    peeked: Mutex<HashMap<i64, GnsNetworkMessage<ToReceive>>>,
+    /// Unparks the driver when the first waker is registered.
+    notify: Notify,
+    /// Handle of the running driver task, respawned if its runtime went away.
+    task: Mutex<Option<JoinHandle<()>>>,
+    tick_micros: AtomicU64,
+}
+
+static DRIVER: OnceLock<Driver> = OnceLock::new();
+
+#[inline]
+fn driver() -> &'static Driver {
+    DRIVER.get_or_init(|| Driver {
+        waiting: Mutex::new(HashMap::new()),
+        peeked: Mutex::new(HashMap::new()),
+        notify: Notify::new(),
+        task: Mutex::new(None),
+        tick_micros: AtomicU64::new(DEFAULT_DRIVER_TICK.as_micros() as _),
+    })
+}
+
+/// The message the driver took from the socket of `queue_id`, if any.
+pub(crate) fn take_peeked(queue_id: i64) -> Option<GnsNetworkMessage<ToReceive>> {
+    DRIVER.get()?.peeked.lock().unwrap().remove(&queue_id)
+}
+
+/// Release what the driver holds for the socket of `queue_id`, once dropped.
+pub(crate) fn forget(queue_id: i64) {
+    if let Some(driver) = DRIVER.get() {
+        driver.waiting.lock().unwrap().remove(&queue_id);
+        driver.peeked.lock().unwrap().remove(&queue_id);
+    }
+}
+
+#[inline]
+fn wake_all(wakers: &mut Vec<Waker>) {
+    for waker in wakers.drain(..) {
+        waker.wake();
+    }
+}
+
+impl Driver {
+    /// Register `cx`'s waker until the socket of `queue_id` gets what it
+    /// waits for, starting the driver task on the current runtime if it is
+    /// not running.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime.
+    fn register(
+        &'static self,
+        global: &'static GnsGlobal,
+        queue_id: i64,
+        source: MessageSource,
+        interest: Interest,
+        cx: &Context<'_>,
+    ) {
+        let was_idle = {
+            let mut waiting = self.waiting.lock().unwrap();
+            let was_idle = waiting.is_empty();
+            let socket = waiting.entry(queue_id).or_insert_with(|| Waiting {
+                source,
+                messages: Vec::new(),
+                events: Vec::new(),
+            });
+            let wakers = match interest {
+                Interest::Message => &mut socket.messages,
+                Interest::Event => &mut socket.events,
+            };
+            // One socket rarely has more than a couple of waiting tasks.
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            was_idle
+        };
+        {
+            let mut task = self.task.lock().unwrap();
+            if task.as_ref().is_none_or(JoinHandle::is_finished) {
+                *task = Some(tokio::spawn(self.run(global)));
+            }
+        }
+        if was_idle {
+            self.notify.notify_one();
+        }
+    }
+
+    /// Wake the tasks of the sockets that got what they wait for, and forget
+    /// the sockets gone. Returns whether any task was woken.
+    fn wake_ready(&self, global: &GnsGlobal) -> bool {
+        // Held throughout, so that no socket is dropped while its handle is
+        // in use, see `GnsGlobal::remove_queue`.
+        let queues = global.event_queues.read().unwrap();
+        let mut waiting = self.waiting.lock().unwrap();
+        let mut peeked = self.peeked.lock().unwrap();
+        let mut woken = false;
+        waiting.retain(|queue_id, socket| {
+            let Some(queue) = queues.get(queue_id).and_then(|entry| entry.queue.upgrade()) else {
+                return false;
+            };
+            if !socket.events.is_empty() && !queue.is_empty() {
+                wake_all(&mut socket.events);
+                woken = true;
+            }
+            if !socket.messages.is_empty() {
+                let ready = peeked.contains_key(queue_id)
+                    || match socket.source.take() {
+                        Ok(Some(message)) => {
+                            peeked.insert(*queue_id, message);
+                            true
+                        }
+                        Ok(None) => false,
+                        // The task surfaces the error.
+                        Err(_) => true,
+                    };
+                if ready {
+                    wake_all(&mut socket.messages);
+                    woken = true;
+                }
+            }
+            !(socket.events.is_empty() && socket.messages.is_empty())
+        });
+        woken
+    }
+
+    async fn run(&'static self, global: &'static GnsGlobal) {
+        let mut backoff = 1;
+        loop {
+            if self.waiting.lock().unwrap().is_empty() {
+                self.notify.notified().await;
+                backoff = 1;
+            }
+            if global.options().manual_poll {
+                global.poll(Duration::ZERO);
+            }
+            global.poll_callbacks();
+            backoff = if self.wake_ready(global) {
+                1
+            } else {
+                (backoff * 2).min(MAX_BACKOFF)
+            };
+            let tick = Duration::from_micros(self.tick_micros.load(Ordering::Relaxed));
+            tokio::time::sleep(tick * backoff).await;
+        }
+    }
+}
+
+impl GnsGlobal {
+    /// Set the interval at which the async driver runs the low-level callbacks
+    /// and re-checks waiting sockets. Defaults to [`DEFAULT_DRIVER_TICK`].
+    /// Shorter ticks lower the receive latency at the cost of CPU. While the
+    /// waiting sockets get nothing, the interval doubles on each tick up to
+    /// 16 times `tick`.
+    pub fn set_driver_tick(&self, tick: Duration) {
+        driver()
+            .tick_micros
+            .store(tick.as_micros().max(1) as _, Ordering::Relaxed);
+    }
+}
+
+impl<S> GnsSocket<S>
+where
+    S: IsReady,
+{
+    /// Wait for the next message on this socket.
+    ///
+    /// The low-level callbacks are run by a background driver spawned on the
+    /// current tokio runtime, there is no need to call
+    /// [`GnsGlobal::poll_callbacks`] while awaiting.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: a message is only taken from GNS in the
+    /// same poll that returns it, so dropping the future (e.g. in a losing
+    /// `select!` branch) never loses or leaks one.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Receive`] if the
+    /// underlying connection or poll group handle is invalid.
+    ///
+    /// # Panics
+    /// Panics if polled outside of a tokio runtime.
+    pub async fn recv_message(&self) -> GnsResult<GnsNetworkMessage<ToReceive>> {
+        poll_fn(|cx| self.poll_recv_message(cx)).await
+    }
+
+    /// Wait for the next connection event on this socket.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: an event is only popped from the queue in
+    /// the same poll that returns it.
+    ///
+    /// # Panics
+    /// Panics if polled outside of a tokio runtime.
+    pub async fn next_event(&self) -> GnsConnectionEvent {
+        poll_fn(|cx| self.poll_next_event(cx)).await
+    }
+
+    /// Poll-based variant of [`recv_message`](Self::recv_message).
+    pub fn poll_recv_message(
+        &self,
+        cx: &mut Context<'_>,
+    ) -> Poll<GnsResult<GnsNetworkMessage<ToReceive>>> {
+        let mut slots = [MessageSlot::uninit()];
+        match self.receive_slots(&mut slots) {
+            Err(e) => Poll::Ready(Err(e)),
+            // Safety: GNS initialized the single slot it reported.
+            Ok(1) => Poll::Ready(Ok(unsafe { take_message(&slots[0]) })),
+            Ok(_) => {
+                driver().register(
+                    self.global,
+                    self.state.queue_id(),
+                    self.state.message_source(),
+                    Interest::Message,
+                    cx,
+                );
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Poll-based variant of [`next_event`](Self::next_event).
+    pub fn poll_next_event(&self, cx: &mut Context<'_>) -> Poll<GnsConnectionEvent> {
+        match self.state.queue().pop() {
+            Some(event) => Poll::Ready(event),
+            None => {
+                driver().register(
+                    self.global,
+                    self.state.queue_id(),
+                    self.state.message_source(),
+                    Interest::Event,
+                    cx,
+                );
+                Poll::Pending
+            }
+        }
+    }
+}
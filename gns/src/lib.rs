@@ -62,16 +62,65 @@ use crossbeam_queue::SegQueue;
 pub use gns_sys as sys;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::{c_void, CStr, CString},
     marker::PhantomData,
     mem::MaybeUninit,
-    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
     sync::{Arc, Mutex, OnceLock, RwLock, Weak},
     time::Duration,
 };
 use sys::*;
 
+mod auth;
+pub use auth::{GnsAuth, GnsAuthStatus};
+
+pub mod config;
+use config::ConfigOption;
+pub use config::{ConfigKey, ConfigScope, ConfigValue, ConfigValueInfo, GnsConfigValue};
+
+mod builder;
+pub use builder::GnsSocketBuilder;
+
+mod budget;
+pub use budget::{Backpressure, BudgetedSend, SendBudget, SendBudgets};
+
+mod debug;
+
+mod diagnostics;
+pub use diagnostics::{GnsDiagnostics, LogLevel, LogSubsystem};
+
+#[cfg(feature = "p2p")]
+mod messages;
+#[cfg(feature = "p2p")]
+pub use messages::{GnsMessages, GnsMessagesEvent};
+
+mod status;
+pub use status::DetailedStatus;
+
+mod quality;
+pub use quality::{QualityEvent, QualityIssue, QualityMonitor, QualitySample, QualityThresholds};
+
+#[cfg(feature = "tokio")]
+mod driver;
+#[cfg(feature = "tokio")]
+pub use driver::DEFAULT_DRIVER_TICK;
+
+#[cfg(feature = "metrics")]
+mod sampler;
+#[cfg(feature = "metrics")]
+pub use sampler::GnsMetricsSampler;
+
+#[cfg(feature = "p2p")]
+mod p2p;
+#[cfg(feature = "p2p")]
+pub use p2p::{IsPeer, LocalSignaling, Signaling, SignalingFactory, SignalingRecvContext};
+
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "futures")]
+pub use stream::{EventStream, MessageSink, MessageStream, SendError};
+
 #[inline]
 fn get_interface() -> *mut ISteamNetworkingSockets {
     unsafe { SteamAPI_SteamNetworkingSockets_v009() }
@@ -93,18 +142,30 @@ pub enum GnsError {
     Init(String),
     #[error("listen failed: invalid handle")]
     Listen,
+    #[error("listen socket not owned by this server")]
+    UnknownListener,
     #[error("connect failed: invalid handle")]
     Connect,
     #[error("receive failed: invalid connection or poll group handle")]
     Receive,
     #[error("accept failed: could not set connection poll group")]
     Accept,
+    #[error("poll group creation failed")]
+    PollGroup,
     #[error("close failed: invalid connection handle")]
     Close,
     #[error("steam api: {0:?}")]
     Api(EResult),
     #[error("config: {0}")]
     Config(&'static str),
+    #[error("invalid or unresolvable address: {0}")]
+    Address(String),
+    #[error("invalid identity: {0}")]
+    Identity(String),
+    #[error("authentication: {0}")]
+    Auth(String),
+    #[error("no session with the peer")]
+    Session,
 }
 
 pub type GnsResult<T> = Result<T, GnsError>;
@@ -124,18 +185,61 @@ fn check(e: EResult) -> GnsResult<()> {
 /// A reference can be retrieved via [`GnsGlobal::get()`], which will initialize
 /// *GameNetworkingSockets* if it has not yet been initialized.
 pub struct GnsGlobal {
-    utils: GnsUtils,
+    utils: &'static GnsUtils,
+    auth: GnsAuth,
+    diagnostics: GnsDiagnostics,
+    #[cfg(feature = "p2p")]
+    messages: GnsMessages,
+    options: GnsInitOptions,
     next_queue_id: AtomicI64,
     /// Per-socket event-queue registry. Reads dominate (one lookup per
     /// connection-state callback from the GNS service thread); writes
     /// happen only on socket creation / drop and on the rare race where
     /// a callback fires for a just-dropped socket. `RwLock` lets future
     /// observability paths read concurrently without contending.
-    event_queues: RwLock<HashMap<i64, Weak<SegQueue<GnsConnectionEvent>>>>,
+    event_queues: RwLock<HashMap<i64, QueueEntry>>,
+    /// Queue the events of each listen socket, and of each connection not
+    /// accepted through one, go to. Kept on our side so that the
+    /// `ConnectionUserData` slot of GNS is left to the user.
+    routes: RwLock<HashMap<Route, i64>>,
+}
+
+/// Key of the [`GnsGlobal`] routes: the connections accepted through a
+/// listen socket share its route.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Route {
+    Listen(HSteamListenSocket),
+    Connection(HSteamNetConnection),
+}
+
+/// Entry of the [`GnsGlobal`] event-queue registry.
+struct QueueEntry {
+    queue: Weak<SegQueue<GnsConnectionEvent>>,
+    /// Identity the remote end must have once connected, and the reason to
+    /// close with otherwise, see [`GnsSocket::connect_to_identity`].
+    expected_identity: Option<(GnsIdentity, EndReason)>,
+    /// Connections with events routed here, until destroyed or closed, see
+    /// [`GnsSocket::connections`].
+    connections: Mutex<HashSet<GnsConnection>>,
 }
 
 static GNS_GLOBAL: OnceLock<GnsGlobal> = OnceLock::new();
 
+/// The [`GnsGlobal::utils`], shared with the operations needing them.
+static GNS_UTILS: GnsUtils = GnsUtils(());
+
+/// Options applied once, when *GameNetworkingSockets* is initialized by
+/// [`GnsGlobal::get_with`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GnsInitOptions {
+    /// Do not start the GNS service thread. Network I/O then only happens
+    /// on the caller's thread, inside [`GnsGlobal::poll`].
+    pub manual_poll: bool,
+    /// Identity of this process, announced to peers. GNS picks a default
+    /// (local) identity when `None`.
+    pub identity: Option<GnsIdentity>,
+}
+
 impl Drop for GnsGlobal {
     #[inline]
     fn drop(&mut self) {
@@ -165,6 +269,30 @@ impl GnsGlobal {
         if let Some(g) = GNS_GLOBAL.get() {
             return Ok(g);
         }
+        Self::init(GnsInitOptions::default())
+    }
+
+    /// Like [`GnsGlobal::get`], initializing *GameNetworkingSockets* with
+    /// `options` if it has not yet been initialized.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Init`] if initialization fails, or if GnsGlobal
+    /// was already initialized with different options.
+    pub fn get_with(options: GnsInitOptions) -> GnsResult<&'static Self> {
+        let g = match GNS_GLOBAL.get() {
+            Some(g) => g,
+            None => Self::init(options)?,
+        };
+        if g.options != options {
+            return Err(GnsError::Init(format!(
+                "already initialized with {:?}",
+                g.options
+            )));
+        }
+        Ok(g)
+    }
+
+    fn init(options: GnsInitOptions) -> GnsResult<&'static Self> {
         // use get_or_try_init once stabilized: https://github.com/rust-lang/rust/issues/109737
         static INIT_LOCK: Mutex<()> = Mutex::new(());
         let _guard = INIT_LOCK.lock().unwrap();
@@ -172,8 +300,14 @@ impl GnsGlobal {
             return Ok(g);
         }
         unsafe {
+            // Must be set before `Init` so the service thread is never started.
+            SteamNetworkingSockets_SetManualPollMode(options.manual_poll);
             let mut error: SteamDatagramErrMsg = MaybeUninit::zeroed().assume_init();
-            if !GameNetworkingSockets_Init(core::ptr::null(), &mut error) {
+            let identity = options
+                .identity
+                .as_ref()
+                .map_or(core::ptr::null(), |identity| &identity.0 as *const _);
+            if !GameNetworkingSockets_Init(identity, &mut error) {
                 return Err(GnsError::Init(
                     CStr::from_ptr(error.as_ptr())
                         .to_str()
@@ -183,9 +317,15 @@ impl GnsGlobal {
             }
         }
         let _ = GNS_GLOBAL.set(GnsGlobal {
-            utils: GnsUtils(()),
+            utils: &GNS_UTILS,
+            auth: GnsAuth::new(&GNS_UTILS),
+            diagnostics: GnsDiagnostics(&GNS_UTILS),
+            #[cfg(feature = "p2p")]
+            messages: GnsMessages::new(),
+            options,
             next_queue_id: AtomicI64::new(0),
             event_queues: RwLock::new(HashMap::new()),
+            routes: RwLock::new(HashMap::new()),
         });
         Ok(GNS_GLOBAL.get().expect("impossible; qed;"))
     }
@@ -197,9 +337,46 @@ impl GnsGlobal {
         }
     }
 
+    /// Process pending network I/O on the calling thread, blocking until some
+    /// happens or `timeout` expires. Connection-status callbacks raised by the
+    /// processed I/O are still only dispatched by [`GnsGlobal::poll_callbacks`].
+    ///
+    /// Only meaningful in [`manual_poll`](GnsInitOptions::manual_poll) mode.
+    /// Otherwise the GNS service thread does the I/O and this simply sleeps
+    /// for `timeout`, so the same stepping loop works in both modes.
+    pub fn poll(&self, timeout: Duration) {
+        if self.options.manual_poll {
+            let ms = timeout.as_millis().min(core::ffi::c_int::MAX as u128);
+            unsafe { SteamNetworkingSockets_Poll(ms as _) }
+        } else {
+            std::thread::sleep(timeout);
+        }
+    }
+
+    #[inline]
+    pub fn options(&self) -> GnsInitOptions {
+        self.options
+    }
+
+    /// The identity of this process, `None` if it is not yet known (e.g.
+    /// while waiting on a certificate).
+    pub fn identity(&self) -> Option<GnsIdentity> {
+        let mut identity = GnsIdentity::default();
+        unsafe { SteamAPI_ISteamNetworkingSockets_GetIdentity(get_interface(), &mut identity.0) }
+            .then_some(identity)
+    }
+
+    /// Discard the certificate and key of this process and switch to
+    /// `identity`, or to the default one when `None`. Only connections made
+    /// afterwards are affected.
+    pub fn reset_identity(&self, identity: Option<&GnsIdentity>) {
+        let identity = identity.map_or(core::ptr::null(), |identity| &identity.0 as *const _);
+        unsafe { SteamAPI_ISteamNetworkingSockets_ResetIdentity(get_interface(), identity) }
+    }
+
     #[inline]
     pub fn utils(&self) -> &GnsUtils {
-        &self.utils
+        self.utils
     }
 
     #[inline]
@@ -207,25 +384,180 @@ impl GnsGlobal {
         self.event_queues.read().unwrap().len()
     }
 
+    /// Create an empty [`GnsPollGroup`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::PollGroup`] if GNS fails to allocate the group.
+    pub fn create_poll_group(&self) -> GnsResult<GnsPollGroup> {
+        let poll_group =
+            unsafe { SteamAPI_ISteamNetworkingSockets_CreatePollGroup(get_interface()) };
+        if poll_group == k_HSteamNetPollGroup_Invalid {
+            Err(GnsError::PollGroup)
+        } else {
+            Ok(GnsPollGroup(poll_group))
+        }
+    }
+
     #[inline]
-    fn create_queue(&self) -> (i64, Arc<SegQueue<GnsConnectionEvent>>) {
+    fn create_queue(
+        &self,
+        expected_identity: Option<(GnsIdentity, EndReason)>,
+    ) -> (i64, Arc<SegQueue<GnsConnectionEvent>>) {
         let queue = Arc::new(SegQueue::new());
         let queue_id = self.next_queue_id.fetch_add(1, Ordering::SeqCst);
-        self.event_queues
-            .write()
-            .unwrap()
-            .insert(queue_id, Arc::downgrade(&queue));
+        self.event_queues.write().unwrap().insert(
+            queue_id,
+            QueueEntry {
+                queue: Arc::downgrade(&queue),
+                expected_identity,
+                connections: Mutex::new(HashSet::new()),
+            },
+        );
         (queue_id, queue)
     }
+
+    /// Create a listen socket or connection with `create`, routing its
+    /// events to `queue_id`. The routes stay locked meanwhile, so that none
+    /// of its events can be dispatched before it is routed. A connection is
+    /// tracked by the queue right away, see [`GnsSocket::connections`].
+    fn create_routed<H: Copy>(
+        &self,
+        queue_id: i64,
+        route: impl FnOnce(H) -> Route,
+        create: impl FnOnce() -> Option<H>,
+    ) -> Option<H> {
+        let mut routes = self.routes.write().unwrap();
+        let handle = create()?;
+        let route = route(handle);
+        routes.insert(route, queue_id);
+        if let Route::Connection(connection) = route {
+            if let Some(entry) = self.event_queues.read().unwrap().get(&queue_id) {
+                entry
+                    .connections
+                    .lock()
+                    .unwrap()
+                    .insert(GnsConnection(connection));
+            }
+        }
+        Some(handle)
+    }
+
+    #[inline]
+    fn unroute(&self, route: Route) {
+        self.routes.write().unwrap().remove(&route);
+    }
+
+    /// Remove the event queue of a dropped socket, and release what the
+    /// async driver holds for it.
+    #[inline]
+    fn remove_queue(&self, queue_id: i64) {
+        self.event_queues.write().unwrap().remove(&queue_id);
+        #[cfg(feature = "tokio")]
+        driver::forget(queue_id);
+    }
 }
 
 /// Opaque wrapper around the low-level [`sys::HSteamListenSocket`].
+///
+/// Identifies one of the listen sockets of a [`GnsSocket<IsServer>`], see
+/// [`GnsSocket::add_listener`]. The socket itself is owned, and closed, by
+/// the server.
 #[repr(transparent)]
-pub(crate) struct GnsListenSocket(HSteamListenSocket);
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GnsListenSocket(HSteamListenSocket);
 
-/// Opaque wrapper around the low-level [`sys::HSteamNetPollGroup`].
+/// Owned wrapper around the low-level [`sys::HSteamNetPollGroup`].
+///
+/// A poll group receives the messages of every connection attached to it in
+/// a single call, see [`GnsGlobal::create_poll_group`]. Groups are `Send` and
+/// `Sync`: connections can be sharded across groups each polled by its own
+/// worker thread, while the connection events keep flowing to the socket the
+/// connection belongs to.
+///
+/// Dropping the group destroys it; its connections are then in no group
+/// until attached to another one.
 #[repr(transparent)]
-pub(crate) struct GnsPollGroup(HSteamNetPollGroup);
+#[derive(Debug)]
+pub struct GnsPollGroup(HSteamNetPollGroup);
+
+impl Drop for GnsPollGroup {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_DestroyPollGroup(get_interface(), self.0);
+        }
+    }
+}
+
+impl GnsPollGroup {
+    /// Move `connection` into this group, removing it from its previous one.
+    /// Its pending, not yet received, messages move along with it.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultInvalidParam` if
+    /// `connection` is not a valid handle.
+    pub fn attach(&self, connection: GnsConnection) -> GnsResult<()> {
+        if unsafe {
+            SteamAPI_ISteamNetworkingSockets_SetConnectionPollGroup(
+                get_interface(),
+                connection.0,
+                self.0,
+            )
+        } {
+            Ok(())
+        } else {
+            Err(GnsError::Api(EResult::k_EResultInvalidParam))
+        }
+    }
+
+    fn receive(&self, slots: &mut [MessageSlot]) -> GnsResult<usize> {
+        let result = unsafe {
+            SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(
+                get_interface(),
+                self.0,
+                slots.as_mut_ptr() as _,
+                slots.len() as _,
+            ) as _
+        };
+        if result == usize::MAX {
+            Err(GnsError::Receive)
+        } else {
+            #[cfg(feature = "metrics")]
+            sampler::record_received(result);
+            Ok(result)
+        }
+    }
+
+    /// Receive up to `K` messages from the connections of this group, see
+    /// [`GnsSocket::receive_messages`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Receive`] if the poll group handle is invalid.
+    pub fn receive_messages<const K: usize>(&self) -> GnsResult<ReceivedMessages<K>> {
+        let mut slots: [MessageSlot; K] = [const { MessageSlot::uninit() }; K];
+        let len = self.receive(&mut slots)?;
+        Ok(ReceivedMessages {
+            slots,
+            cursor: SlotCursor { len, pos: 0 },
+        })
+    }
+
+    /// Receive up to `buffer.len()` messages into a caller-owned `buffer`, see
+    /// [`GnsSocket::receive_messages_into`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Receive`] if the poll group handle is invalid.
+    pub fn receive_messages_into<'a>(
+        &self,
+        buffer: &'a mut [MessageSlot],
+    ) -> GnsResult<ReceivedMessagesInto<'a>> {
+        let len = self.receive(buffer)?;
+        Ok(ReceivedMessagesInto {
+            slots: buffer,
+            cursor: SlotCursor { len, pos: 0 },
+        })
+    }
+}
 
 /// Initial state of a [`GnsSocket`].
 /// This state represent a socket that has not been used as a Server or Client implementation.
@@ -233,9 +565,39 @@ pub(crate) struct GnsPollGroup(HSteamNetPollGroup);
 pub struct IsCreated;
 
 mod private {
-    pub trait Sealed {}
-    impl Sealed for super::IsServer {}
-    impl Sealed for super::IsClient {}
+    pub trait Sealed {
+        /// Id of the event queue of the socket in the [`GnsGlobal`](super::GnsGlobal) registry.
+        fn queue_id(&self) -> i64;
+        /// Handle the async driver takes pending messages from.
+        #[cfg(feature = "tokio")]
+        fn message_source(&self) -> super::driver::MessageSource;
+    }
+
+    impl Sealed for super::IsServer {
+        #[inline]
+        fn queue_id(&self) -> i64 {
+            self.queue_id
+        }
+
+        #[cfg(feature = "tokio")]
+        #[inline]
+        fn message_source(&self) -> super::driver::MessageSource {
+            super::driver::MessageSource::PollGroup(self.poll_group.0)
+        }
+    }
+
+    impl Sealed for super::IsClient {
+        #[inline]
+        fn queue_id(&self) -> i64 {
+            self.queue_id
+        }
+
+        #[cfg(feature = "tokio")]
+        #[inline]
+        fn message_source(&self) -> super::driver::MessageSource {
+            super::driver::MessageSource::Connection(self.connection.0)
+        }
+    }
 }
 
 /// Common functions available for any [`GnsSocket`] state that is implementing it.
@@ -255,25 +617,26 @@ pub struct IsServer {
     queue: Arc<SegQueue<GnsConnectionEvent>>,
     queue_id: i64,
     global: &'static GnsGlobal,
-    listen_socket: GnsListenSocket,
+    /// Every listen socket feeds the same poll group and event queue.
+    listen_sockets: Mutex<Vec<GnsListenSocket>>,
     poll_group: GnsPollGroup,
+    /// Options of the socket builder, applied to every listen socket.
+    options: Vec<ConfigOption>,
 }
 
 impl Drop for IsServer {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            SteamAPI_ISteamNetworkingSockets_CloseListenSocket(
-                get_interface(),
-                self.listen_socket.0,
-            );
-            SteamAPI_ISteamNetworkingSockets_DestroyPollGroup(get_interface(), self.poll_group.0);
+            for listen_socket in self.listen_sockets.get_mut().unwrap().drain(..) {
+                SteamAPI_ISteamNetworkingSockets_CloseListenSocket(
+                    get_interface(),
+                    listen_socket.0,
+                );
+                self.global.unroute(Route::Listen(listen_socket.0));
+            }
         }
-        self.global
-            .event_queues
-            .write()
-            .unwrap()
-            .remove(&self.queue_id);
+        self.global.remove_queue(self.queue_id);
     }
 }
 
@@ -283,20 +646,9 @@ impl IsReady for IsServer {
         &self.queue
     }
 
+    #[inline]
     fn receive(&self, slots: &mut [MaybeUninit<*mut ISteamNetworkingMessage>]) -> GnsResult<usize> {
-        let result = unsafe {
-            SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(
-                get_interface(),
-                self.poll_group.0,
-                slots.as_mut_ptr() as _,
-                slots.len() as _,
-            ) as _
-        };
-        if result == usize::MAX {
-            Err(GnsError::Receive)
-        } else {
-            Ok(result)
-        }
+        self.poll_group.receive(slots)
     }
 }
 
@@ -320,11 +672,8 @@ impl Drop for IsClient {
                 false,
             );
         }
-        self.global
-            .event_queues
-            .write()
-            .unwrap()
-            .remove(&self.queue_id);
+        self.global.unroute(Route::Connection(self.connection.0));
+        self.global.remove_queue(self.queue_id);
     }
 }
 
@@ -346,6 +695,8 @@ impl IsReady for IsClient {
         if result == usize::MAX {
             Err(GnsError::Receive)
         } else {
+            #[cfg(feature = "metrics")]
+            sampler::record_received(result);
             Ok(result)
         }
     }
@@ -651,6 +1002,10 @@ unsafe impl Payload for &'static str {
 #[repr(transparent)]
 pub struct GnsNetworkMessage<T>(*mut ISteamNetworkingMessage, PhantomData<T>);
 
+// Safety: a message is exclusively owned by its wrapper, GNS lets any thread
+// release it, and `ToSend` payloads are `Send` by the `Payload` bound.
+unsafe impl<T> Send for GnsNetworkMessage<T> {}
+
 impl<T> Drop for GnsNetworkMessage<T> {
     #[inline]
     fn drop(&mut self) {
@@ -708,10 +1063,24 @@ impl<T> GnsNetworkMessage<T> {
         GnsConnection(unsafe { (*self.0).m_conn })
     }
 
+    /// User data of the connection when the message was received, see
+    /// [`GnsSocket::set_connection_user_data`].
     #[inline]
     pub fn connection_user_data(&self) -> u64 {
         unsafe { (*self.0).m_nConnUserData as _ }
     }
+
+    /// Identity of the peer that sent, or will receive, the message.
+    #[inline]
+    pub fn peer_identity(&self) -> GnsIdentity {
+        GnsIdentity(unsafe { (*self.0).m_identityPeer })
+    }
+
+    /// Channel of a message exchanged through `GnsMessages` (`p2p` feature).
+    #[inline]
+    pub fn channel(&self) -> i32 {
+        unsafe { (*self.0).m_nChannel }
+    }
 }
 
 impl GnsNetworkMessage<ToSend> {
@@ -771,6 +1140,12 @@ impl GnsConnection {
         Self(handle)
     }
 
+    /// The raw `HSteamNetConnection` handle.
+    #[inline]
+    pub const fn into_raw(self) -> HSteamNetConnection {
+        self.0
+    }
+
     /// `true` if this is not the GNS invalid-connection sentinel (`0`).
     #[inline]
     pub fn is_valid(self) -> bool {
@@ -778,106 +1153,879 @@ impl GnsConnection {
     }
 }
 
-#[derive(Default, Copy, Clone)]
-pub struct GnsConnectionInfo(SteamNetConnectionInfo_t);
-
-impl GnsConnectionInfo {
-    #[inline]
-    pub fn state(&self) -> ESteamNetworkingConnectionState {
-        self.0.m_eState
-    }
+/// Rust-native mirror of [`ESteamNetworkingConnectionState`].
+///
+/// `FinWait`, `Linger` and `Dead` are internal GNS states that are never
+/// reported through the public API, they are kept for completeness.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    /// Dummy value used to indicate an error condition, or a connection
+    /// handle that has been destroyed.
+    None,
+    /// We are trying to establish whether peers can talk to each other.
+    Connecting,
+    /// Some connection types use a back channel or trusted third party for
+    /// the initial handshake, this state means we are looking for a route.
+    FindingRoute,
+    /// We received communications from our peer (and we know who they are)
+    /// and are all good.
+    Connected,
+    /// Connection has been closed by our peer, but not closed locally.
+    ClosedByPeer,
+    /// A disruption in the connection has been detected locally.
+    ProblemDetectedLocally,
+    /// Internal GNS state: closed locally, waiting for the peer to
+    /// acknowledge. Never reported through the public API.
+    FinWait,
+    /// Internal GNS state: closed locally, still flushing reliable data.
+    /// Never reported through the public API.
+    Linger,
+    /// Internal GNS state: the connection is gone but its handle is not
+    /// released yet. Never reported through the public API.
+    Dead,
+}
 
+impl ConnectionState {
+    /// `true` for the two states in which the connection is gone but the
+    /// handle must still be released with [`GnsSocket::close_connection`].
     #[inline]
-    pub fn end_reason(&self) -> u32 {
-        self.0.m_eEndReason as u32
+    pub fn is_closed(self) -> bool {
+        matches!(self, Self::ClosedByPeer | Self::ProblemDetectedLocally)
     }
+}
 
-    #[inline]
-    pub fn end_debug(&self) -> &str {
-        unsafe { CStr::from_ptr(self.0.m_szEndDebug.as_ptr()) }
-            .to_str()
-            .unwrap_or("")
+impl From<ESteamNetworkingConnectionState> for ConnectionState {
+    #[inline]
+    fn from(state: ESteamNetworkingConnectionState) -> Self {
+        use ESteamNetworkingConnectionState::*;
+        match state {
+            k_ESteamNetworkingConnectionState_Connecting => Self::Connecting,
+            k_ESteamNetworkingConnectionState_FindingRoute => Self::FindingRoute,
+            k_ESteamNetworkingConnectionState_Connected => Self::Connected,
+            k_ESteamNetworkingConnectionState_ClosedByPeer => Self::ClosedByPeer,
+            k_ESteamNetworkingConnectionState_ProblemDetectedLocally => {
+                Self::ProblemDetectedLocally
+            }
+            k_ESteamNetworkingConnectionState_FinWait => Self::FinWait,
+            k_ESteamNetworkingConnectionState_Linger => Self::Linger,
+            k_ESteamNetworkingConnectionState_Dead => Self::Dead,
+            // `_Force32Bit` is a sizing sentinel that GNS never produces.
+            k_ESteamNetworkingConnectionState_None
+            | k_ESteamNetworkingConnectionState__Force32Bit => Self::None,
+        }
     }
+}
 
-    #[inline]
-    pub fn remote_address(&self) -> IpAddr {
-        let ipv4 = unsafe { self.0.m_addrRemote.__bindgen_anon_1.m_ipv4 };
-        if ipv4.m_8zeros == 0 && ipv4.m_0000 == 0 && ipv4.m_ffff == 0xffff {
-            IpAddr::from(Ipv4Addr::from(ipv4.m_ip))
-        } else {
-            IpAddr::from(Ipv6Addr::from(unsafe {
-                self.0.m_addrRemote.__bindgen_anon_1.m_ipv6
-            }))
+impl From<ConnectionState> for ESteamNetworkingConnectionState {
+    #[inline]
+    fn from(state: ConnectionState) -> Self {
+        use ESteamNetworkingConnectionState::*;
+        match state {
+            ConnectionState::None => k_ESteamNetworkingConnectionState_None,
+            ConnectionState::Connecting => k_ESteamNetworkingConnectionState_Connecting,
+            ConnectionState::FindingRoute => k_ESteamNetworkingConnectionState_FindingRoute,
+            ConnectionState::Connected => k_ESteamNetworkingConnectionState_Connected,
+            ConnectionState::ClosedByPeer => k_ESteamNetworkingConnectionState_ClosedByPeer,
+            ConnectionState::ProblemDetectedLocally => {
+                k_ESteamNetworkingConnectionState_ProblemDetectedLocally
+            }
+            ConnectionState::FinWait => k_ESteamNetworkingConnectionState_FinWait,
+            ConnectionState::Linger => k_ESteamNetworkingConnectionState_Linger,
+            ConnectionState::Dead => k_ESteamNetworkingConnectionState_Dead,
         }
     }
+}
 
-    #[inline]
-    pub fn remote_port(&self) -> u16 {
-        self.0.m_addrRemote.m_port
-    }
+/// Meaningful classification of a `(old_state, new_state)` pair carried by a
+/// [`GnsConnectionEvent`], see [`GnsConnectionEvent::transition`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionTransition {
+    /// A remote peer is connecting to one of our listen sockets. The
+    /// connection must be accepted with [`GnsSocket::accept`] or closed.
+    IncomingRequest,
+    /// A connection we initiated via [`GnsSocket::connect`] started its
+    /// handshake.
+    Connecting,
+    /// The handshake is waiting for a route to the peer.
+    FindingRoute,
+    /// The connection is established, messages can flow.
+    Connected,
+    /// The peer closed the connection. The handle must still be released
+    /// with [`GnsSocket::close_connection`].
+    ClosedByPeer,
+    /// The connection was lost (timeout, bad crypt...). The handle must
+    /// still be released with [`GnsSocket::close_connection`].
+    ProblemDetectedLocally,
+    /// The connection handle has been released and is no longer valid. Also
+    /// reported, with the reason it was closed with, for a connection closed
+    /// by [`GnsSocket::connect_to_identity`] on an identity mismatch.
+    Destroyed,
+    /// Any other pair of states, not expected through the public API.
+    Other {
+        old: ConnectionState,
+        new: ConnectionState,
+    },
 }
 
-#[derive(Debug, Default, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
-pub struct GnsConnectionRealTimeLaneStatus(SteamNetConnectionRealTimeLaneStatus_t);
+/// Typed view over the `ESteamNetConnectionEnd` ranges carried by
+/// [`GnsConnectionInfo::end_reason`] and accepted by
+/// [`GnsSocket::close_connection`].
+///
+/// Application codes hold the offset from the start of their range, i.e.
+/// `App(5)` is `k_ESteamNetConnectionEnd_App_Min + 5`. The offset must be
+/// below `1000`, and the raw value of an `Other` cause within its range, see
+/// [`EndReason::is_valid`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EndReason {
+    /// `k_ESteamNetConnectionEnd_Invalid`, no reason available (yet).
+    Invalid,
+    /// Normal, application-defined close. `App(0)` is
+    /// `k_ESteamNetConnectionEnd_App_Generic`.
+    App(u16),
+    /// Exceptional, application-defined close. `AppException(0)` is
+    /// `k_ESteamNetConnectionEnd_AppException_Generic`.
+    AppException(u16),
+    /// A problem with the local host or its connection to the Internet.
+    Local(LocalEndReason),
+    /// A problem with the remote host, or the peer did something wrong.
+    Remote(RemoteEndReason),
+    /// Other problems, usually timeouts or internal errors.
+    Misc(MiscEndReason),
+    /// A value outside of every documented range.
+    Unknown(u32),
+}
 
-impl GnsConnectionRealTimeLaneStatus {
-    #[inline]
-    pub fn pending_bytes_unreliable(&self) -> u32 {
-        self.0.m_cbPendingUnreliable as _
+/// Causes in the `k_ESteamNetConnectionEnd_Local_*` range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LocalEndReason {
+    OfflineMode,
+    ManyRelayConnectivity,
+    HostedServerPrimaryRelay,
+    NetworkConfig,
+    Rights,
+    P2PIceNoPublicAddresses,
+    /// Raw value of a cause in the local range not listed above.
+    Other(u32),
+}
+
+/// Causes in the `k_ESteamNetConnectionEnd_Remote_*` range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RemoteEndReason {
+    /// The remote host stopped answering our packets.
+    Timeout,
+    BadCrypt,
+    BadCert,
+    BadProtocolVersion,
+    P2PIceNoPublicAddresses,
+    /// Raw value of a cause in the remote range not listed above.
+    Other(u32),
+}
+
+/// Causes in the `k_ESteamNetConnectionEnd_Misc_*` range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MiscEndReason {
+    Generic,
+    InternalError,
+    /// The connection timed out, possibly during the handshake.
+    Timeout,
+    SteamConnectivity,
+    NoRelaySessionsToClient,
+    P2PRendezvous,
+    P2PNatFirewall,
+    PeerSentNoConnection,
+    /// Raw value of a cause in the misc range not listed above.
+    Other(u32),
+}
+
+impl EndReason {
+    const APP_MIN: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_App_Min as _;
+    const APP_MAX: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_App_Max as _;
+    const APP_EXCEPTION_MIN: u32 =
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_AppException_Min as _;
+    const APP_EXCEPTION_MAX: u32 =
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_AppException_Max as _;
+    const LOCAL_MIN: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Local_Min as _;
+    const LOCAL_MAX: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Local_Max as _;
+    const REMOTE_MIN: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Remote_Min as _;
+    const REMOTE_MAX: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Remote_Max as _;
+    const MISC_MIN: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Misc_Min as _;
+    const MISC_MAX: u32 = ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Misc_Max as _;
+
+    /// A reason for [`GnsSocket::connect_to_identity`] to close the
+    /// connections whose remote end has another identity with. GNS only
+    /// forwards application reasons to the peer, hence the last code of the
+    /// exception range; pass another one if your application already uses it.
+    pub const IDENTITY_MISMATCH: EndReason = EndReason::AppException(999);
+
+    /// Classify a raw `ESteamNetConnectionEnd` value.
+    pub fn from_raw(raw: u32) -> Self {
+        match raw {
+            0 => Self::Invalid,
+            Self::APP_MIN..=Self::APP_MAX => Self::App((raw - Self::APP_MIN) as u16),
+            Self::APP_EXCEPTION_MIN..=Self::APP_EXCEPTION_MAX => {
+                Self::AppException((raw - Self::APP_EXCEPTION_MIN) as u16)
+            }
+            Self::LOCAL_MIN..=Self::LOCAL_MAX => {
+                Self::Local(lookup_cause(&LOCAL_END_REASONS, raw, LocalEndReason::Other))
+            }
+            Self::REMOTE_MIN..=Self::REMOTE_MAX => Self::Remote(lookup_cause(
+                &REMOTE_END_REASONS,
+                raw,
+                RemoteEndReason::Other,
+            )),
+            Self::MISC_MIN..=Self::MISC_MAX => {
+                Self::Misc(lookup_cause(&MISC_END_REASONS, raw, MiscEndReason::Other))
+            }
+            raw => Self::Unknown(raw),
+        }
+    }
+
+    /// The raw `ESteamNetConnectionEnd` value, as passed to / reported by GNS.
+    /// Only meaningful if the reason [`is_valid`](Self::is_valid): `App(1000)`
+    /// is the raw value of `AppException(0)`.
+    pub fn raw(self) -> u32 {
+        match self {
+            Self::Invalid => 0,
+            Self::App(offset) => Self::APP_MIN + offset as u32,
+            Self::AppException(offset) => Self::APP_EXCEPTION_MIN + offset as u32,
+            Self::Local(LocalEndReason::Other(raw))
+            | Self::Remote(RemoteEndReason::Other(raw))
+            | Self::Misc(MiscEndReason::Other(raw))
+            | Self::Unknown(raw) => raw,
+            Self::Local(cause) => lookup_raw(&LOCAL_END_REASONS, cause),
+            Self::Remote(cause) => lookup_raw(&REMOTE_END_REASONS, cause),
+            Self::Misc(cause) => lookup_raw(&MISC_END_REASONS, cause),
+        }
     }
 
+    /// `true` if the reason lies within its documented range, i.e. converts
+    /// to a raw value and back unchanged. Every reason reported by GNS is.
     #[inline]
-    pub fn pending_bytes_reliable(&self) -> u32 {
-        self.0.m_cbPendingReliable as _
+    pub fn is_valid(self) -> bool {
+        Self::from_raw(self.raw()) == self
     }
 
+    /// `true` if the application closed the connection, normally or not.
     #[inline]
-    pub fn bytes_sent_unacked_reliable(&self) -> u32 {
-        self.0.m_cbSentUnackedReliable as _
+    pub fn is_app(self) -> bool {
+        matches!(self, Self::App(_) | Self::AppException(_))
     }
 
+    /// `true` if the connection ended because a peer stopped answering.
     #[inline]
-    pub fn approximated_queue_time(&self) -> Duration {
-        Duration::from_micros(self.0.m_usecQueueTime as _)
+    pub fn is_timeout(self) -> bool {
+        matches!(
+            self,
+            Self::Remote(RemoteEndReason::Timeout) | Self::Misc(MiscEndReason::Timeout)
+        )
     }
 }
 
-#[derive(Default, Debug, Copy, Clone, PartialOrd, PartialEq)]
-pub struct GnsConnectionRealTimeStatus(SteamNetConnectionRealTimeStatus_t);
+/// Named causes of each problem range, shared by both directions of the
+/// [`EndReason`] conversion so they cannot drift apart.
+const LOCAL_END_REASONS: [(ESteamNetConnectionEnd, LocalEndReason); 6] = {
+    use ESteamNetConnectionEnd::*;
+    [
+        (
+            k_ESteamNetConnectionEnd_Local_OfflineMode,
+            LocalEndReason::OfflineMode,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Local_ManyRelayConnectivity,
+            LocalEndReason::ManyRelayConnectivity,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Local_HostedServerPrimaryRelay,
+            LocalEndReason::HostedServerPrimaryRelay,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Local_NetworkConfig,
+            LocalEndReason::NetworkConfig,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Local_Rights,
+            LocalEndReason::Rights,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Local_P2P_ICE_NoPublicAddresses,
+            LocalEndReason::P2PIceNoPublicAddresses,
+        ),
+    ]
+};
 
-impl GnsConnectionRealTimeStatus {
-    #[inline]
-    pub fn state(&self) -> ESteamNetworkingConnectionState {
-        self.0.m_eState
-    }
+const REMOTE_END_REASONS: [(ESteamNetConnectionEnd, RemoteEndReason); 5] = {
+    use ESteamNetConnectionEnd::*;
+    [
+        (
+            k_ESteamNetConnectionEnd_Remote_Timeout,
+            RemoteEndReason::Timeout,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Remote_BadCrypt,
+            RemoteEndReason::BadCrypt,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Remote_BadCert,
+            RemoteEndReason::BadCert,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Remote_BadProtocolVersion,
+            RemoteEndReason::BadProtocolVersion,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Remote_P2P_ICE_NoPublicAddresses,
+            RemoteEndReason::P2PIceNoPublicAddresses,
+        ),
+    ]
+};
 
-    #[inline]
-    pub fn ping(&self) -> u32 {
-        self.0.m_nPing as _
-    }
+const MISC_END_REASONS: [(ESteamNetConnectionEnd, MiscEndReason); 8] = {
+    use ESteamNetConnectionEnd::*;
+    [
+        (
+            k_ESteamNetConnectionEnd_Misc_Generic,
+            MiscEndReason::Generic,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_InternalError,
+            MiscEndReason::InternalError,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_Timeout,
+            MiscEndReason::Timeout,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_SteamConnectivity,
+            MiscEndReason::SteamConnectivity,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_NoRelaySessionsToClient,
+            MiscEndReason::NoRelaySessionsToClient,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_P2P_Rendezvous,
+            MiscEndReason::P2PRendezvous,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_P2P_NAT_Firewall,
+            MiscEndReason::P2PNatFirewall,
+        ),
+        (
+            k_ESteamNetConnectionEnd_Misc_PeerSentNoConnection,
+            MiscEndReason::PeerSentNoConnection,
+        ),
+    ]
+};
 
-    #[inline]
-    pub fn quality_local(&self) -> f32 {
-        self.0.m_flConnectionQualityLocal
-    }
+#[inline]
+fn lookup_cause<T: Copy>(
+    table: &[(ESteamNetConnectionEnd, T)],
+    raw: u32,
+    other: impl FnOnce(u32) -> T,
+) -> T {
+    table
+        .iter()
+        .find(|(e, _)| *e as u32 == raw)
+        .map(|(_, cause)| *cause)
+        .unwrap_or_else(|| other(raw))
+}
 
-    #[inline]
-    pub fn quality_remote(&self) -> f32 {
-        self.0.m_flConnectionQualityRemote
-    }
+#[inline]
+fn lookup_raw<T: Copy + PartialEq>(table: &[(ESteamNetConnectionEnd, T)], cause: T) -> u32 {
+    table
+        .iter()
+        .find(|(_, c)| *c == cause)
+        .map(|(e, _)| *e as u32)
+        .expect("every named cause has a table entry; qed;")
+}
 
+impl From<u32> for EndReason {
     #[inline]
-    pub fn out_packets_per_sec(&self) -> f32 {
-        self.0.m_flOutPacketsPerSec
+    fn from(raw: u32) -> Self {
+        Self::from_raw(raw)
     }
+}
 
+impl From<EndReason> for u32 {
     #[inline]
-    pub fn out_bytes_per_sec(&self) -> f32 {
-        self.0.m_flOutBytesPerSec
+    fn from(reason: EndReason) -> Self {
+        reason.raw()
     }
+}
 
-    #[inline]
-    pub fn in_packets_per_sec(&self) -> f32 {
+/// Maximum length of the string form of a [`GnsIpAddr`], NUL terminator
+/// included (`SteamNetworkingIPAddr::k_cchMaxString`).
+const IP_ADDR_MAX_STRING: usize = 48;
+
+/// Resolve `addresses`, surfacing failures as [`GnsError::Address`].
+fn resolve(addresses: impl ToSocketAddrs) -> GnsResult<impl Iterator<Item = SocketAddr>> {
+    addresses
+        .to_socket_addrs()
+        .map_err(|e| GnsError::Address(e.to_string()))
+}
+
+/// Safe wrapper around the low-level [`sys::SteamNetworkingIPAddr`]: an IPv6
+/// address, IPv4 being stored IPv4-mapped, and a port.
+///
+/// Converts from and to [`SocketAddr`] without loss, except for the IPv6 flow
+/// info and scope id that GNS does not store, and IPv4-mapped IPv6 addresses
+/// that come back as IPv4. Parsing and formatting go
+/// through GNS, so they accept and produce what GNS itself logs, e.g.
+/// `127.0.0.1:27015` or `[::1]:27015`.
+#[repr(transparent)]
+#[derive(Default, Copy, Clone)]
+pub struct GnsIpAddr(SteamNetworkingIPAddr);
+
+impl GnsIpAddr {
+    #[inline]
+    pub fn new(ip: IpAddr, port: u16) -> Self {
+        SocketAddr::new(ip, port).into()
+    }
+
+    /// The IP address, IPv4-mapped addresses being unmapped.
+    pub fn ip(&self) -> IpAddr {
+        let ipv4 = unsafe { self.0.__bindgen_anon_1.m_ipv4 };
+        if ipv4.m_8zeros == 0 && ipv4.m_0000 == 0 && ipv4.m_ffff == 0xffff {
+            IpAddr::from(Ipv4Addr::from(ipv4.m_ip))
+        } else {
+            IpAddr::from(Ipv6Addr::from(unsafe { self.0.__bindgen_anon_1.m_ipv6 }))
+        }
+    }
+
+    #[inline]
+    pub fn port(&self) -> u16 {
+        self.0.m_port
+    }
+}
+
+impl From<SocketAddr> for GnsIpAddr {
+    fn from(address: SocketAddr) -> Self {
+        let ip = match address.ip() {
+            IpAddr::V4(ip) => SteamNetworkingIPAddr__bindgen_ty_2 {
+                m_ipv4: SteamNetworkingIPAddr_IPv4MappedAddress {
+                    m_8zeros: 0,
+                    m_0000: 0,
+                    m_ffff: 0xffff,
+                    m_ip: ip.octets(),
+                },
+            },
+            IpAddr::V6(ip) => SteamNetworkingIPAddr__bindgen_ty_2 {
+                m_ipv6: ip.octets(),
+            },
+        };
+        GnsIpAddr(SteamNetworkingIPAddr {
+            __bindgen_anon_1: ip,
+            m_port: address.port(),
+        })
+    }
+}
+
+impl From<GnsIpAddr> for SocketAddr {
+    #[inline]
+    fn from(address: GnsIpAddr) -> Self {
+        SocketAddr::new(address.ip(), address.port())
+    }
+}
+
+impl core::str::FromStr for GnsIpAddr {
+    type Err = GnsError;
+
+    /// Parse an IPv4 or IPv6 address, with an optional port (`0` if absent).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let c = CString::new(s).map_err(|_| GnsError::Address(s.to_owned()))?;
+        let mut address = GnsIpAddr::default();
+        if unsafe { SteamAPI_SteamNetworkingIPAddr_ParseString(&mut address.0, c.as_ptr()) } {
+            Ok(address)
+        } else {
+            Err(GnsError::Address(s.to_owned()))
+        }
+    }
+}
+
+impl core::fmt::Display for GnsIpAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut buf = [0 as core::ffi::c_char; IP_ADDR_MAX_STRING];
+        let mut raw = self.0;
+        unsafe {
+            SteamAPI_SteamNetworkingIPAddr_ToString(
+                &mut raw,
+                buf.as_mut_ptr(),
+                buf.len() as _,
+                true,
+            );
+            f.write_str(&CStr::from_ptr(buf.as_ptr()).to_string_lossy())
+        }
+    }
+}
+
+impl core::fmt::Debug for GnsIpAddr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "GnsIpAddr({self})")
+    }
+}
+
+impl PartialEq for GnsIpAddr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        SocketAddr::from(*self) == SocketAddr::from(*other)
+    }
+}
+
+impl Eq for GnsIpAddr {}
+
+impl core::hash::Hash for GnsIpAddr {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        SocketAddr::from(*self).hash(state)
+    }
+}
+
+/// Maximum length of the string form of a [`GnsIdentity`], NUL terminator
+/// included (`SteamNetworkingIdentity::k_cchMaxString`).
+const IDENTITY_MAX_STRING: usize = 128;
+
+/// Safe wrapper around the low-level [`sys::SteamNetworkingIdentity`], the
+/// identity a peer is known and authenticated by.
+///
+/// The string form is the one of GNS, e.g. `str:lobby-server`,
+/// `ip:192.168.0.1:27015`, `gen:0a0b0c` or `steamid:76561197960287930`.
+#[repr(transparent)]
+#[derive(Default, Copy, Clone)]
+pub struct GnsIdentity(SteamNetworkingIdentity);
+
+impl GnsIdentity {
+    pub fn from_steam_id(steam_id: u64) -> Self {
+        let mut identity = Self::default();
+        unsafe { SteamAPI_SteamNetworkingIdentity_SetSteamID64(&mut identity.0, steam_id) };
+        identity
+    }
+
+    pub fn from_ip(address: impl Into<GnsIpAddr>) -> Self {
+        let mut identity = Self::default();
+        let address = address.into();
+        unsafe { SteamAPI_SteamNetworkingIdentity_SetIPAddr(&mut identity.0, &address.0) };
+        identity
+    }
+
+    /// # Errors
+    /// Returns [`GnsError::Identity`] if `s` is empty, longer than 31 bytes
+    /// or contains a NUL.
+    pub fn from_generic_string(s: &str) -> GnsResult<Self> {
+        let c = CString::new(s).map_err(|_| GnsError::Identity(s.to_owned()))?;
+        let mut identity = Self::default();
+        if unsafe { SteamAPI_SteamNetworkingIdentity_SetGenericString(&mut identity.0, c.as_ptr()) }
+        {
+            Ok(identity)
+        } else {
+            Err(GnsError::Identity(s.to_owned()))
+        }
+    }
+
+    /// # Errors
+    /// Returns [`GnsError::Identity`] if `bytes` is empty or longer than 32
+    /// bytes.
+    pub fn from_generic_bytes(bytes: &[u8]) -> GnsResult<Self> {
+        let mut identity = Self::default();
+        if unsafe {
+            SteamAPI_SteamNetworkingIdentity_SetGenericBytes(
+                &mut identity.0,
+                bytes.as_ptr() as _,
+                bytes.len() as _,
+            )
+        } {
+            Ok(identity)
+        } else {
+            Err(GnsError::Identity(format!("{bytes:02x?}")))
+        }
+    }
+
+    #[inline]
+    pub fn is_invalid(&self) -> bool {
+        self.kind() == ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_Invalid
+    }
+
+    #[inline]
+    pub fn kind(&self) -> ESteamNetworkingIdentityType {
+        self.0.m_eType
+    }
+
+    #[inline]
+    pub fn steam_id(&self) -> Option<u64> {
+        (self.kind() == ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_SteamID)
+            .then_some(unsafe { self.0.__bindgen_anon_1.m_steamID64 })
+    }
+
+    #[inline]
+    pub fn ip(&self) -> Option<GnsIpAddr> {
+        (self.kind() == ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_IPAddress)
+            .then_some(GnsIpAddr(unsafe { self.0.__bindgen_anon_1.m_ip }))
+    }
+
+    pub fn generic_string(&self) -> Option<&str> {
+        if self.kind() != ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_GenericString
+        {
+            return None;
+        }
+        // Safety: GNS keeps generic strings NUL-terminated within the union.
+        let s = unsafe {
+            CStr::from_ptr(core::ptr::addr_of!(self.0.__bindgen_anon_1) as *const core::ffi::c_char)
+        };
+        s.to_str().ok()
+    }
+
+    #[inline]
+    pub fn generic_bytes(&self) -> Option<&[u8]> {
+        (self.kind() == ESteamNetworkingIdentityType::k_ESteamNetworkingIdentityType_GenericBytes)
+            .then(|| self.raw_bytes())
+    }
+
+    /// The `m_cbSize` meaningful bytes of the identity, what GNS compares.
+    #[inline]
+    fn raw_bytes(&self) -> &[u8] {
+        let len = (self.0.m_cbSize.max(0) as usize).min(IDENTITY_MAX_STRING);
+        // Safety: the union is at least `IDENTITY_MAX_STRING` bytes long and
+        // `u8` has no alignment requirement.
+        unsafe {
+            core::slice::from_raw_parts(
+                core::ptr::addr_of!(self.0.__bindgen_anon_1) as *const u8,
+                len,
+            )
+        }
+    }
+}
+
+impl core::str::FromStr for GnsIdentity {
+    type Err = GnsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let c = CString::new(s).map_err(|_| GnsError::Identity(s.to_owned()))?;
+        let mut identity = GnsIdentity::default();
+        if unsafe { SteamAPI_SteamNetworkingIdentity_ParseString(&mut identity.0, c.as_ptr()) } {
+            Ok(identity)
+        } else {
+            Err(GnsError::Identity(s.to_owned()))
+        }
+    }
+}
+
+impl core::fmt::Display for GnsIdentity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut buf = [0 as core::ffi::c_char; IDENTITY_MAX_STRING];
+        let mut raw = self.0;
+        unsafe {
+            SteamAPI_SteamNetworkingIdentity_ToString(&mut raw, buf.as_mut_ptr(), buf.len() as _);
+            f.write_str(&CStr::from_ptr(buf.as_ptr()).to_string_lossy())
+        }
+    }
+}
+
+impl core::fmt::Debug for GnsIdentity {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "GnsIdentity({self})")
+    }
+}
+
+/// Same semantic as `SteamNetworkingIdentity::operator==`: kind, size and
+/// meaningful bytes.
+impl PartialEq for GnsIdentity {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind() && self.raw_bytes() == other.raw_bytes()
+    }
+}
+
+impl Eq for GnsIdentity {}
+
+impl core::hash::Hash for GnsIdentity {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (self.kind() as i32).hash(state);
+        self.raw_bytes().hash(state);
+    }
+}
+
+bitflags::bitflags! {
+    /// Type-safe wrapper over the GNS `k_nSteamNetworkConnectionInfoFlags_*`
+    /// flags, describing how a connection is carried.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct ConnectionInfoFlags: i32 {
+        /// The remote identity is not authenticated by a certificate.
+        const UNAUTHENTICATED  = sys::k_nSteamNetworkConnectionInfoFlags_Unauthenticated;
+        /// Traffic is not encrypted.
+        const UNENCRYPTED      = sys::k_nSteamNetworkConnectionInfoFlags_Unencrypted;
+        /// Both ends are in this process, exchanging buffers in memory.
+        const LOOPBACK_BUFFERS = sys::k_nSteamNetworkConnectionInfoFlags_LoopbackBuffers;
+        /// A fast route is used, e.g. LAN or loopback.
+        const FAST             = sys::k_nSteamNetworkConnectionInfoFlags_Fast;
+        /// Traffic goes through a relay.
+        const RELAYED          = sys::k_nSteamNetworkConnectionInfoFlags_Relayed;
+        /// Traffic is duplicated over a second network interface.
+        const DUAL_WIFI        = sys::k_nSteamNetworkConnectionInfoFlags_DualWifi;
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+pub struct GnsConnectionInfo(SteamNetConnectionInfo_t);
+
+impl GnsConnectionInfo {
+    #[inline]
+    pub fn state(&self) -> ConnectionState {
+        self.0.m_eState.into()
+    }
+
+    #[inline]
+    pub fn end_reason(&self) -> EndReason {
+        EndReason::from_raw(self.0.m_eEndReason as u32)
+    }
+
+    #[inline]
+    pub fn end_debug(&self) -> &str {
+        unsafe { CStr::from_ptr(self.0.m_szEndDebug.as_ptr()) }
+            .to_str()
+            .unwrap_or("")
+    }
+
+    #[inline]
+    pub fn remote_address(&self) -> IpAddr {
+        GnsIpAddr(self.0.m_addrRemote).ip()
+    }
+
+    #[inline]
+    pub fn remote_port(&self) -> u16 {
+        self.0.m_addrRemote.m_port
+    }
+
+    #[inline]
+    pub fn remote_socket_addr(&self) -> SocketAddr {
+        GnsIpAddr(self.0.m_addrRemote).into()
+    }
+
+    /// Identity of the remote end, `None` while it is not known yet.
+    #[inline]
+    pub fn remote_identity(&self) -> Option<GnsIdentity> {
+        let identity = GnsIdentity(self.0.m_identityRemote);
+        (!identity.is_invalid()).then_some(identity)
+    }
+
+    /// User data of the connection when the info was taken, see
+    /// [`GnsSocket::set_connection_user_data`].
+    #[inline]
+    pub fn user_data(&self) -> u64 {
+        self.0.m_nUserData as _
+    }
+
+    /// How the connection is carried, e.g. whether it is encrypted.
+    #[inline]
+    pub fn flags(&self) -> ConnectionInfoFlags {
+        ConnectionInfoFlags::from_bits_retain(self.0.m_nFlags)
+    }
+
+    /// Description GNS gives the connection in its debug output, e.g.
+    /// `#1234 UDP 127.0.0.1:27015 'name'`.
+    #[inline]
+    pub fn description(&self) -> &str {
+        unsafe { CStr::from_ptr(self.0.m_szConnectionDescription.as_ptr()) }
+            .to_str()
+            .unwrap_or("")
+    }
+
+    /// The listen socket the connection arrived on, `None` for connections
+    /// initiated locally.
+    #[inline]
+    pub fn listen_socket(&self) -> Option<GnsListenSocket> {
+        let handle = self.0.m_hListenSocket;
+        (handle != k_HSteamListenSocket_Invalid).then_some(GnsListenSocket(handle))
+    }
+}
+
+impl core::fmt::Display for GnsConnectionInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.description() {
+            "" => write!(f, "{}", self.remote_socket_addr()),
+            description => f.write_str(description),
+        }
+    }
+}
+
+impl core::fmt::Debug for GnsConnectionInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GnsConnectionInfo")
+            .field("description", &self.description())
+            .field("state", &self.state())
+            .field("end_reason", &self.end_reason())
+            .field("end_debug", &self.end_debug())
+            .field("remote_socket_addr", &self.remote_socket_addr())
+            .field("remote_identity", &self.remote_identity())
+            .field("listen_socket", &self.listen_socket())
+            .field("flags", &self.flags())
+            .field("user_data", &self.user_data())
+            .finish()
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct GnsConnectionRealTimeLaneStatus(SteamNetConnectionRealTimeLaneStatus_t);
+
+impl GnsConnectionRealTimeLaneStatus {
+    #[inline]
+    pub fn pending_bytes_unreliable(&self) -> u32 {
+        self.0.m_cbPendingUnreliable as _
+    }
+
+    #[inline]
+    pub fn pending_bytes_reliable(&self) -> u32 {
+        self.0.m_cbPendingReliable as _
+    }
+
+    #[inline]
+    pub fn bytes_sent_unacked_reliable(&self) -> u32 {
+        self.0.m_cbSentUnackedReliable as _
+    }
+
+    #[inline]
+    pub fn approximated_queue_time(&self) -> Duration {
+        Duration::from_micros(self.0.m_usecQueueTime as _)
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct GnsConnectionRealTimeStatus(SteamNetConnectionRealTimeStatus_t);
+
+impl GnsConnectionRealTimeStatus {
+    #[inline]
+    pub fn state(&self) -> ConnectionState {
+        self.0.m_eState.into()
+    }
+
+    #[inline]
+    pub fn ping(&self) -> u32 {
+        self.0.m_nPing as _
+    }
+
+    #[inline]
+    pub fn quality_local(&self) -> f32 {
+        self.0.m_flConnectionQualityLocal
+    }
+
+    #[inline]
+    pub fn quality_remote(&self) -> f32 {
+        self.0.m_flConnectionQualityRemote
+    }
+
+    #[inline]
+    pub fn out_packets_per_sec(&self) -> f32 {
+        self.0.m_flOutPacketsPerSec
+    }
+
+    #[inline]
+    pub fn out_bytes_per_sec(&self) -> f32 {
+        self.0.m_flOutBytesPerSec
+    }
+
+    #[inline]
+    pub fn in_packets_per_sec(&self) -> f32 {
         self.0.m_flInPacketsPerSec
     }
 
@@ -933,8 +2081,38 @@ pub struct GnsConnectionEvent(SteamNetConnectionStatusChangedCallback_t);
 
 impl GnsConnectionEvent {
     #[inline]
-    pub fn old_state(&self) -> ESteamNetworkingConnectionState {
-        self.0.m_eOldState
+    pub fn old_state(&self) -> ConnectionState {
+        self.0.m_eOldState.into()
+    }
+
+    /// Classify the state change carried by this event.
+    ///
+    /// A `None -> Connecting` change is reported as
+    /// [`ConnectionTransition::IncomingRequest`] when the connection arrived
+    /// on a listen socket, and [`ConnectionTransition::Connecting`] when it
+    /// was initiated locally.
+    pub fn transition(&self) -> ConnectionTransition {
+        let old = self.old_state();
+        let new = self.info().state();
+        match (old, new) {
+            (ConnectionState::None, ConnectionState::Connecting) => {
+                if self.listen_socket().is_some() {
+                    ConnectionTransition::IncomingRequest
+                } else {
+                    ConnectionTransition::Connecting
+                }
+            }
+            (_, ConnectionState::FindingRoute) => ConnectionTransition::FindingRoute,
+            (_, ConnectionState::Connected) => ConnectionTransition::Connected,
+            (_, ConnectionState::ClosedByPeer) => ConnectionTransition::ClosedByPeer,
+            (_, ConnectionState::ProblemDetectedLocally) => {
+                ConnectionTransition::ProblemDetectedLocally
+            }
+            (old, ConnectionState::None) if old != ConnectionState::None => {
+                ConnectionTransition::Destroyed
+            }
+            (old, new) => ConnectionTransition::Other { old, new },
+        }
     }
 
     #[inline]
@@ -942,6 +2120,14 @@ impl GnsConnectionEvent {
         GnsConnection(self.0.m_hConn)
     }
 
+    /// The listen socket the connection arrived on, `None` for connections
+    /// initiated locally.
+    #[inline]
+    pub fn listen_socket(&self) -> Option<GnsListenSocket> {
+        let handle = self.0.m_info.m_hListenSocket;
+        (handle != k_HSteamListenSocket_Invalid).then_some(GnsListenSocket(handle))
+    }
+
     #[inline]
     pub fn info(&self) -> GnsConnectionInfo {
         GnsConnectionInfo(self.0.m_info)
@@ -962,6 +2148,13 @@ where
 {
     /// Get a connection lane status.
     /// This call is possible only if lanes has been previously configured using configure_connection_lanes
+    ///
+    /// Fetching the status resets the jitter high water mark of GNS, see
+    /// [`GnsConnectionRealTimeStatus::max_jitter_usec`]. Everything reading
+    /// the status of a connection shares that mark: [`QualityMonitor::poll`],
+    /// [`GnsSocket::detailed_status_parsed`], [`GnsSocket::backpressure`] and
+    /// the `metrics` sampler each only see the jitter since the status was
+    /// last fetched, by any of them.
     pub fn get_connection_real_time_status(
         &self,
         GnsConnection(conn): GnsConnection,
@@ -1000,6 +2193,39 @@ where
         }
     }
 
+    /// Attach `user_data`, e.g. a session id, to `connection`. It is given
+    /// back by [`GnsNetworkMessage::connection_user_data`] for the messages
+    /// received on the connection, and by [`GnsConnectionInfo::user_data`].
+    /// Connections start with `u64::MAX`, unless
+    /// [`config::CONNECTION_USER_DATA`] is set when creating them.
+    ///
+    /// Event routing doesn't depend on it, so any value can be set.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultNoConnection` if
+    /// `connection` doesn't exist.
+    pub fn set_connection_user_data(
+        &self,
+        GnsConnection(conn): GnsConnection,
+        user_data: u64,
+    ) -> GnsResult<()> {
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_SetConnectionUserData(
+                get_interface(),
+                conn,
+                user_data as _,
+            )
+        }
+        .then_some(())
+        .ok_or(GnsError::Api(EResult::k_EResultNoConnection))
+    }
+
+    /// User data of `connection`, `None` if it doesn't exist.
+    pub fn connection_user_data(&self, connection: GnsConnection) -> Option<u64> {
+        self.get_connection_info(connection)
+            .map(|info| info.user_data())
+    }
+
     pub fn flush_messages_on_connection(
         &self,
         GnsConnection(conn): GnsConnection,
@@ -1009,35 +2235,108 @@ where
         })
     }
 
-    /// Close a connection. `pszDebug` is forwarded to the peer if non-`None`;
+    /// Close a connection. `debug` is forwarded to the peer if non-`None`;
     /// pass `None` to send no diagnostic string and avoid all allocation.
     ///
+    /// The message is copied into a NUL-terminated buffer and cut at its
+    /// first interior NUL, if any. Use
+    /// [`close_connection_cstr`](Self::close_connection_cstr) to skip the copy
+    /// when you already have a `CStr`.
+    ///
     /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultInvalidParam` if `reason` is
+    /// neither [`EndReason::Invalid`] nor a valid application reason, see
+    /// [`EndReason::is_valid`]; GNS would replace it with a generic code.
     /// Returns [`GnsError::Close`] if the connection handle is invalid (e.g.
     /// already closed).
     pub fn close_connection(
+        &self,
+        conn: GnsConnection,
+        reason: EndReason,
+        debug: Option<&str>,
+        linger: bool,
+    ) -> GnsResult<()> {
+        let debug = debug.map(|d| {
+            let d = d.split('\0').next().unwrap_or("");
+            CString::new(d).expect("interior NUL stripped above; qed;")
+        });
+        self.close_connection_cstr(conn, reason, debug.as_deref(), linger)
+    }
+
+    /// Zero-allocation variant of [`close_connection`](Self::close_connection);
+    /// `&CStr` already carries a trailing NUL.
+    ///
+    /// # Errors
+    /// Same as [`close_connection`](Self::close_connection).
+    pub fn close_connection_cstr(
         &self,
         GnsConnection(conn): GnsConnection,
-        reason: u32,
+        reason: EndReason,
         debug: Option<&CStr>,
         linger: bool,
     ) -> GnsResult<()> {
+        if reason != EndReason::Invalid && !(reason.is_app() && reason.is_valid()) {
+            return Err(GnsError::Api(EResult::k_EResultInvalidParam));
+        }
         let debug_ptr = debug.map(|d| d.as_ptr()).unwrap_or(core::ptr::null());
         if unsafe {
             SteamAPI_ISteamNetworkingSockets_CloseConnection(
                 get_interface(),
                 conn,
-                reason as _,
+                reason.raw() as _,
                 debug_ptr,
                 linger,
             )
         } {
+            // GNS raises no event for the connections we close.
+            if let Some(entry) = self
+                .global
+                .event_queues
+                .read()
+                .unwrap()
+                .get(&self.state.queue_id())
+            {
+                entry
+                    .connections
+                    .lock()
+                    .unwrap()
+                    .remove(&GnsConnection(conn));
+            }
             Ok(())
         } else {
             Err(GnsError::Close)
         }
     }
 
+    /// Connections GNS reported a state change of to this socket, and that
+    /// were neither destroyed nor closed with
+    /// [`close_connection`](Self::close_connection) since. Incoming requests
+    /// not accepted yet are included.
+    pub fn connections(&self) -> Vec<GnsConnection> {
+        self.global
+            .event_queues
+            .read()
+            .unwrap()
+            .get(&self.state.queue_id())
+            .map(|entry| entry.connections.lock().unwrap().iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Receive up to `slots.len()` messages into `slots`, starting with the
+    /// one the async driver took from GNS, if any.
+    fn receive_slots(&self, slots: &mut [MessageSlot]) -> GnsResult<usize> {
+        #[cfg(feature = "tokio")]
+        if let Some(slot) = slots.first_mut() {
+            if let Some(message) = driver::take_peeked(self.state.queue_id()) {
+                slot.write(unsafe { message.into_inner() });
+                // The error surfaces on the next call, once the message is
+                // out.
+                return Ok(1 + self.state.receive(&mut slots[1..]).unwrap_or(0));
+            }
+        }
+        self.state.receive(slots)
+    }
+
     /// Receive up to `K` messages, returning an iterator over the ones that
     /// were available. Each message is yielded by value, so the caller may keep
     /// it (store or forward it) or let it drop, which releases it back to GNS;
@@ -1053,7 +2352,7 @@ where
     /// group handle is invalid.
     pub fn receive_messages<const K: usize>(&self) -> GnsResult<ReceivedMessages<K>> {
         let mut slots: [MessageSlot; K] = [const { MessageSlot::uninit() }; K];
-        let len = self.state.receive(&mut slots)?;
+        let len = self.receive_slots(&mut slots)?;
         Ok(ReceivedMessages {
             slots,
             cursor: SlotCursor { len, pos: 0 },
@@ -1075,7 +2374,7 @@ where
         &self,
         buffer: &'a mut [MessageSlot],
     ) -> GnsResult<ReceivedMessagesInto<'a>> {
-        let len = self.state.receive(buffer)?;
+        let len = self.receive_slots(buffer)?;
         Ok(ReceivedMessagesInto {
             slots: buffer,
             cursor: SlotCursor { len, pos: 0 },
@@ -1151,7 +2450,7 @@ where
                 false,
             );
         }
-        result
+        let outcomes: Vec<SendOutcome> = result
             .into_iter()
             .zip(raw)
             .map(|(value, ptr)| {
@@ -1166,13 +2465,16 @@ where
                     SendOutcome::Skipped(GnsNetworkMessage(ptr, PhantomData))
                 }
             })
-            .collect()
+            .collect();
+        #[cfg(feature = "metrics")]
+        sampler::record_outcomes(&outcomes);
+        outcomes
     }
 }
 
 impl GnsSocket<IsCreated> {
-    /// Unsafe, C-like callback, we use the user data to pass the queue ID, so we can find the
-    /// correct queue in GnsGlobal.
+    /// Unsafe, C-like callback, we look the listen socket or connection up in the routes
+    /// of GnsGlobal to find the correct queue.
     unsafe extern "C" fn on_connection_state_changed(
         info: &mut SteamNetConnectionStatusChangedCallback_t,
     ) {
@@ -1180,16 +2482,39 @@ impl GnsSocket<IsCreated> {
             // GnsGlobal needs to be initialized to even reach this point in the first place.
             .expect("GnsGlobal should be initialized");
 
-        let queue_id = info.m_info.m_nUserData as _;
+        let route = if info.m_info.m_hListenSocket != k_HSteamListenSocket_Invalid {
+            Route::Listen(info.m_info.m_hListenSocket)
+        } else {
+            Route::Connection(info.m_hConn)
+        };
+        let Some(queue_id) = gns_global.routes.read().unwrap().get(&route).copied() else {
+            return;
+        };
         // Hot path: take the read lock, look up, push if upgradeable.
         let needs_purge = {
             let queues = gns_global.event_queues.read().unwrap();
-            match queues.get(&queue_id).and_then(Weak::upgrade) {
-                Some(queue) => {
-                    queue.push(GnsConnectionEvent(*info));
-                    false
-                }
-                None => queues.contains_key(&queue_id),
+            match queues.get(&queue_id) {
+                Some(entry) => match entry.queue.upgrade() {
+                    Some(queue) => {
+                        let mut event = GnsConnectionEvent(*info);
+                        if let Some((expected, reason)) = &entry.expected_identity {
+                            Self::enforce_remote_identity(&mut event, expected, *reason);
+                        }
+                        let mut connections = entry.connections.lock().unwrap();
+                        if event.0.m_info.m_eState
+                            == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None
+                        {
+                            connections.remove(&GnsConnection(info.m_hConn));
+                        } else {
+                            connections.insert(GnsConnection(info.m_hConn));
+                        }
+                        drop(connections);
+                        queue.push(event);
+                        false
+                    }
+                    None => true,
+                },
+                None => false,
             }
         };
         // Cold path: race with socket drop, the entry is still in the
@@ -1201,6 +2526,38 @@ impl GnsSocket<IsCreated> {
         }
     }
 
+    /// Close a connection completing with an unexpected remote identity,
+    /// rewriting its `Connected` event into a `Destroyed` one, since the
+    /// handle is released, with the reason sent to the remote end.
+    fn enforce_remote_identity(
+        event: &mut GnsConnectionEvent,
+        expected: &GnsIdentity,
+        reason: EndReason,
+    ) {
+        let info = &mut event.0.m_info;
+        if ConnectionState::from(info.m_eState) != ConnectionState::Connected
+            || GnsIdentity(info.m_identityRemote) == *expected
+        {
+            return;
+        }
+        const DEBUG: &CStr = c"remote identity mismatch";
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_CloseConnection(
+                get_interface(),
+                event.0.m_hConn,
+                reason.raw() as _,
+                DEBUG.as_ptr(),
+                false,
+            );
+        }
+        info.m_eState = ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None;
+        info.m_eEndReason = reason.raw() as _;
+        info.m_szEndDebug = [0; 128];
+        for (dst, &src) in info.m_szEndDebug.iter_mut().zip(DEBUG.to_bytes()) {
+            *dst = src as _;
+        }
+    }
+
     /// Initialize a new socket in [`IsCreated`] state.
     #[inline]
     pub fn new(global: &'static GnsGlobal) -> Self {
@@ -1211,122 +2568,360 @@ impl GnsSocket<IsCreated> {
     }
 
     fn setup_common(
-        address: IpAddr,
-        port: u16,
-        queue_id: int64,
-    ) -> (SteamNetworkingIPAddr, [SteamNetworkingConfigValue_t; 2]) {
-        let addr = SteamNetworkingIPAddr {
-            __bindgen_anon_1: match address {
-                IpAddr::V4(address) => SteamNetworkingIPAddr__bindgen_ty_2 {
-                    m_ipv4: SteamNetworkingIPAddr_IPv4MappedAddress {
-                        m_8zeros: 0,
-                        m_0000: 0,
-                        m_ffff: 0xffff,
-                        m_ip: address.octets(),
-                    },
-                },
-                IpAddr::V6(address) => SteamNetworkingIPAddr__bindgen_ty_2 {
-                    m_ipv6: address.octets(),
-                },
-            },
-            m_port: port,
-        };
-        let options = [SteamNetworkingConfigValue_t {
+        address: SocketAddr,
+        options: &[ConfigOption],
+    ) -> (SteamNetworkingIPAddr, Vec<SteamNetworkingConfigValue_t>) {
+        (
+            GnsIpAddr::from(address).0,
+            Self::connection_options(options),
+        )
+    }
+
+    /// The user `options` followed by the one installing our connection
+    /// status callback on a new connection, or on the connections of a new
+    /// listen socket. GNS applies them in order, so ours can't be
+    /// overridden. The strings of `options` are borrowed.
+    fn connection_options(options: &[ConfigOption]) -> Vec<SteamNetworkingConfigValue_t> {
+        let internal = SteamNetworkingConfigValue_t {
             m_eDataType: ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Ptr,
             m_eValue: ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Callback_ConnectionStatusChanged,
             m_val: SteamNetworkingConfigValue_t__bindgen_ty_1 {
               m_ptr: Self::on_connection_state_changed as *const fn(&SteamNetConnectionStatusChangedCallback_t) as *mut c_void
             }
-          }, SteamNetworkingConfigValue_t {
-            m_eDataType: ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int64,
-            m_eValue: ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_ConnectionUserData,
-            m_val: SteamNetworkingConfigValue_t__bindgen_ty_1 {
-              m_int64: queue_id
-            }
-        }];
-        (addr, options)
+          };
+        options
+            .iter()
+            .map(ConfigOption::raw)
+            .chain([internal])
+            .collect()
+    }
+
+    /// Create a listen socket routing its connection events to `queue_id`.
+    fn create_listen_socket(
+        global: &GnsGlobal,
+        address: SocketAddr,
+        queue_id: i64,
+        options: &[ConfigOption],
+    ) -> GnsResult<GnsListenSocket> {
+        let (addr, options) = Self::setup_common(address, options);
+        global
+            .create_routed(queue_id, Route::Listen, || {
+                let listen_socket = unsafe {
+                    SteamAPI_ISteamNetworkingSockets_CreateListenSocketIP(
+                        get_interface(),
+                        &addr,
+                        options.len() as _,
+                        options.as_ptr(),
+                    )
+                };
+                (listen_socket != k_HSteamListenSocket_Invalid).then_some(listen_socket)
+            })
+            .map(GnsListenSocket)
+            .ok_or(GnsError::Listen)
     }
 
     /// Listen for incoming connections, the socket transition from [`IsCreated`] to [`IsServer`], allowing a new set of server operations.
+    /// More addresses can be listened on later with [`GnsSocket::add_listener`].
+    /// Pass port `0` to let the OS pick a free port, see [`GnsSocket::local_addr`].
+    #[inline]
     pub fn listen(self, address: IpAddr, port: u16) -> GnsResult<GnsSocket<IsServer>> {
-        let (queue_id, queue) = self.global.create_queue();
-        let (addr, options) = Self::setup_common(address, port, queue_id);
-        let listen_socket = unsafe {
-            SteamAPI_ISteamNetworkingSockets_CreateListenSocketIP(
-                get_interface(),
-                &addr,
-                options.len() as _,
-                options.as_ptr(),
-            )
-        };
-        if listen_socket == k_HSteamListenSocket_Invalid {
-            Err(GnsError::Listen)
-        } else {
-            let poll_group =
-                unsafe { SteamAPI_ISteamNetworkingSockets_CreatePollGroup(get_interface()) };
-            if poll_group == k_HSteamNetPollGroup_Invalid {
-                Err(GnsError::Listen)
-            } else {
-                Ok(GnsSocket {
-                    global: self.global,
-                    state: IsServer {
-                        queue,
-                        queue_id,
-                        global: self.global,
-                        listen_socket: GnsListenSocket(listen_socket),
-                        poll_group: GnsPollGroup(poll_group),
-                    },
-                })
-            }
-        }
+        self.listen_on(SocketAddr::new(address, port))
+    }
+
+    /// Like [`GnsSocket::listen`], binding the first of the addresses
+    /// `addresses` resolves to that can be listened on.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Address`] if `addresses` fails to resolve, or
+    /// [`GnsError::Listen`] if none of the resolved addresses can be bound.
+    #[inline]
+    pub fn listen_on(self, addresses: impl ToSocketAddrs) -> GnsResult<GnsSocket<IsServer>> {
+        self.listen_inner(addresses, Vec::new())
+    }
+
+    fn listen_inner(
+        self,
+        addresses: impl ToSocketAddrs,
+        options: Vec<ConfigOption>,
+    ) -> GnsResult<GnsSocket<IsServer>> {
+        let mut addresses = resolve(addresses)?;
+        let (queue_id, queue) = self.global.create_queue(None);
+        let poll_group = self.global.create_poll_group()?;
+        let listen_socket = addresses
+            .find_map(|address| {
+                Self::create_listen_socket(self.global, address, queue_id, &options).ok()
+            })
+            .ok_or(GnsError::Listen)?;
+        Ok(GnsSocket {
+            global: self.global,
+            state: IsServer {
+                queue,
+                queue_id,
+                global: self.global,
+                listen_sockets: Mutex::new(vec![listen_socket]),
+                poll_group,
+                options,
+            },
+        })
     }
 
     /// Connect to a remote host, the socket transition from [`IsCreated`] to [`IsClient`], allowing a new set of client operations.
+    #[inline]
     pub fn connect(self, address: IpAddr, port: u16) -> GnsResult<GnsSocket<IsClient>> {
-        let (queue_id, queue) = self.global.create_queue();
-        let (addr, options) = Self::setup_common(address, port, queue_id);
-        let connection = unsafe {
-            SteamAPI_ISteamNetworkingSockets_ConnectByIPAddress(
+        self.connect_to(SocketAddr::new(address, port))
+    }
+
+    /// Like [`GnsSocket::connect`], to the first of the addresses `addresses`
+    /// resolves to, e.g. `"game.example.com:27015"`. The connection is
+    /// established asynchronously, so the remaining addresses are not tried.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Address`] if `addresses` fails to resolve to any
+    /// address.
+    #[inline]
+    pub fn connect_to(self, addresses: impl ToSocketAddrs) -> GnsResult<GnsSocket<IsClient>> {
+        self.connect_inner(addresses, None, &[])
+    }
+
+    /// Like [`GnsSocket::connect_to`], requiring the remote end to have
+    /// `identity`. If it turns out to have another one, the connection is
+    /// closed with `reason`, which the remote end sees too, e.g.
+    /// [`EndReason::IDENTITY_MISMATCH`]. Instead of
+    /// [`ConnectionTransition::Connected`], it is then reported as
+    /// [`ConnectionTransition::Destroyed`] with `reason` as its
+    /// [end reason](GnsConnectionInfo::end_reason): the handle is already
+    /// released, it must not be closed again.
+    ///
+    /// Without [certificate authentication](GnsGlobal::reset_identity) the
+    /// remote identity is only claimed, not proven, by the peer.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultInvalidParam` if `reason` is
+    /// not a valid application reason, see
+    /// [`close_connection`](GnsSocket::close_connection), and
+    /// [`GnsError::Address`] if `addresses` fails to resolve to any address.
+    #[inline]
+    pub fn connect_to_identity(
+        self,
+        addresses: impl ToSocketAddrs,
+        identity: GnsIdentity,
+        reason: EndReason,
+    ) -> GnsResult<GnsSocket<IsClient>> {
+        self.connect_inner(addresses, Some((identity, reason)), &[])
+    }
+
+    fn connect_inner(
+        self,
+        addresses: impl ToSocketAddrs,
+        expected_identity: Option<(GnsIdentity, EndReason)>,
+        options: &[ConfigOption],
+    ) -> GnsResult<GnsSocket<IsClient>> {
+        if let Some((_, reason)) = &expected_identity {
+            if !(reason.is_app() && reason.is_valid()) {
+                return Err(GnsError::Api(EResult::k_EResultInvalidParam));
+            }
+        }
+        let address = resolve(addresses)?
+            .next()
+            .ok_or_else(|| GnsError::Address("no address resolved".to_owned()))?;
+        let (queue_id, queue) = self.global.create_queue(expected_identity);
+        let (addr, options) = Self::setup_common(address, options);
+        let connection = self
+            .global
+            .create_routed(queue_id, Route::Connection, || {
+                let connection = unsafe {
+                    SteamAPI_ISteamNetworkingSockets_ConnectByIPAddress(
+                        get_interface(),
+                        &addr,
+                        options.len() as _,
+                        options.as_ptr(),
+                    )
+                };
+                (connection != k_HSteamNetConnection_Invalid).then_some(connection)
+            })
+            .ok_or(GnsError::Connect)?;
+        Ok(GnsSocket {
+            global: self.global,
+            state: IsClient {
+                queue,
+                queue_id,
+                global: self.global,
+                connection: GnsConnection(connection),
+            },
+        })
+    }
+
+    /// Create two sockets connected to each other inside this process, without
+    /// binding any port. With `use_network_loopback` the traffic goes through
+    /// the localhost network stack, otherwise it is exchanged in memory.
+    ///
+    /// Both sockets are connected from the start, so no
+    /// [`ConnectionTransition::Connected`] event is raised; later transitions,
+    /// such as the peer closing, are reported as usual.
+    pub fn socket_pair(
+        self,
+        use_network_loopback: bool,
+    ) -> GnsResult<(GnsSocket<IsClient>, GnsSocket<IsClient>)> {
+        let mut first = k_HSteamNetConnection_Invalid;
+        let mut second = k_HSteamNetConnection_Invalid;
+        if !unsafe {
+            SteamAPI_ISteamNetworkingSockets_CreateSocketPair(
                 get_interface(),
-                &addr,
-                options.len() as _,
-                options.as_ptr(),
+                &mut first,
+                &mut second,
+                use_network_loopback,
+                core::ptr::null(),
+                core::ptr::null(),
+            )
+        } {
+            return Err(GnsError::Connect);
+        }
+        // Wrap both ends first so that an error on either closes the two.
+        let first = Self::adopt_connection(self.global, first);
+        let second = Self::adopt_connection(self.global, second);
+        Ok((first?, second?))
+    }
+
+    /// Wrap a connection created without the [`GnsSocket::setup_common`]
+    /// options, routing its connection events to a new queue. Events raised
+    /// before the callback is installed are not reported.
+    fn adopt_connection(
+        global: &'static GnsGlobal,
+        connection: HSteamNetConnection,
+    ) -> GnsResult<GnsSocket<IsClient>> {
+        let (queue_id, queue) = global.create_queue(None);
+        global.create_routed(queue_id, Route::Connection, || Some(connection));
+        let socket = GnsSocket {
+            global,
+            state: IsClient {
+                queue,
+                queue_id,
+                global,
+                connection: GnsConnection(connection),
+            },
+        };
+        let callback = Self::on_connection_state_changed
+            as *const fn(&SteamNetConnectionStatusChangedCallback_t)
+            as *mut c_void;
+        let routed = unsafe {
+            SteamAPI_ISteamNetworkingUtils_SetConfigValue(
+                get_utils(),
+                ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Callback_ConnectionStatusChanged,
+                ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_Connection,
+                connection as _,
+                ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Ptr,
+                &callback as *const *mut c_void as *const c_void,
             )
         };
-        if connection == k_HSteamNetConnection_Invalid {
-            Err(GnsError::Connect)
+        if routed {
+            Ok(socket)
         } else {
-            Ok(GnsSocket {
-                global: self.global,
-                state: IsClient {
-                    queue,
-                    queue_id,
-                    global: self.global,
-                    connection: GnsConnection(connection),
-                },
-            })
+            Err(GnsError::Connect)
         }
     }
 }
 
 impl GnsSocket<IsServer> {
     /// Accept an incoming connection. This operation is available only if the socket is in the [`IsServer`] state.
+    /// The connection is attached to the poll group of the server, see
+    /// [`GnsPollGroup::attach`] to move it to another one.
     pub fn accept(&self, connection: GnsConnection) -> GnsResult<()> {
         check(unsafe {
             SteamAPI_ISteamNetworkingSockets_AcceptConnection(get_interface(), connection.0)
         })?;
-        if !unsafe {
-            SteamAPI_ISteamNetworkingSockets_SetConnectionPollGroup(
+        // Both the poll group and the connection should be valid here, so
+        // this is not expected to fail in practice
+        self.state.poll_group.attach(connection)
+    }
+
+    /// The poll group accepted connections are attached to, and that
+    /// [`GnsSocket::receive_messages`] reads from.
+    #[inline]
+    pub fn poll_group(&self) -> &GnsPollGroup {
+        &self.state.poll_group
+    }
+
+    /// Listen on an additional address. Connections arriving on it share the
+    /// poll group and event queue of the server; use
+    /// [`GnsConnectionEvent::listen_socket`] to tell them apart.
+    pub fn add_listener(&self, address: IpAddr, port: u16) -> GnsResult<GnsListenSocket> {
+        let listen_socket = GnsSocket::<IsCreated>::create_listen_socket(
+            self.global,
+            SocketAddr::new(address, port),
+            self.state.queue_id,
+            &self.state.options,
+        )?;
+        self.state
+            .listen_sockets
+            .lock()
+            .unwrap()
+            .push(listen_socket);
+        Ok(listen_socket)
+    }
+
+    /// Stop listening on `listen_socket`. As with any GNS listen socket, the
+    /// connections accepted through it are closed ungracefully.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::UnknownListener`] if `listen_socket` does not belong
+    /// to this server.
+    pub fn remove_listener(&self, listen_socket: GnsListenSocket) -> GnsResult<()> {
+        let mut listen_sockets = self.state.listen_sockets.lock().unwrap();
+        let index = listen_sockets
+            .iter()
+            .position(|&s| s == listen_socket)
+            .ok_or(GnsError::UnknownListener)?;
+        listen_sockets.remove(index);
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_CloseListenSocket(get_interface(), listen_socket.0);
+        }
+        self.global.unroute(Route::Listen(listen_socket.0));
+        Ok(())
+    }
+
+    /// The listen sockets currently owned by this server, oldest first.
+    pub fn listeners(&self) -> Vec<GnsListenSocket> {
+        self.state.listen_sockets.lock().unwrap().clone()
+    }
+
+    /// The address the server is bound to, with the port actually picked by
+    /// the OS when listening on port `0`. With several listeners this is the
+    /// address of the oldest one, see [`GnsSocket::listener_addr`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::UnknownListener`] if every listener was removed.
+    pub fn local_addr(&self) -> GnsResult<SocketAddr> {
+        let first = self.state.listen_sockets.lock().unwrap().first().copied();
+        self.listener_addr(first.ok_or(GnsError::UnknownListener)?)
+    }
+
+    /// The address `listen_socket` is bound to.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::UnknownListener`] if `listen_socket` does not belong
+    /// to this server.
+    pub fn listener_addr(&self, listen_socket: GnsListenSocket) -> GnsResult<SocketAddr> {
+        if !self
+            .state
+            .listen_sockets
+            .lock()
+            .unwrap()
+            .contains(&listen_socket)
+        {
+            return Err(GnsError::UnknownListener);
+        }
+        let mut addr = SteamNetworkingIPAddr::default();
+        if unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetListenSocketAddress(
                 get_interface(),
-                connection.0,
-                self.state.poll_group.0,
+                listen_socket.0,
+                &mut addr,
             )
         } {
-            // Both the poll group and the connection should be valid here, so
-            // this is not expected to happen in practice
-            return Err(GnsError::Accept);
+            Ok(GnsIpAddr(addr).into())
+        } else {
+            Err(GnsError::UnknownListener)
         }
-        Ok(())
     }
 }
 
@@ -1339,6 +2934,8 @@ impl GnsSocket<IsClient> {
 }
 
 /// The configuration value used to define configure global variables in [`GnsUtils::set_global_config_value`]
+///
+/// Untyped; prefer the [`ConfigKey`]s of [`config`] with [`GnsUtils::set_config`].
 pub enum GnsConfig<'a> {
     Float(f32),
     Int32(i32),
@@ -1352,46 +2949,7 @@ pub enum GnsConfig<'a> {
 
 pub struct GnsUtils(());
 
-type MsgPtr = *const ::std::os::raw::c_char;
-
-/// User-supplied debug callback. `Send + Sync` because it is invoked from the
-/// GNS service thread, and may capture state shared with the caller's threads.
-type DebugCallback = dyn Fn(ESteamNetworkingSocketsDebugOutputType, &str) + Send + Sync + 'static;
-
-/// Set once via [`GnsUtils::enable_debug_output`]; invoked from the GNS service
-/// thread, so the underlying `OnceLock` is the synchronization point.
-static DEBUG_CB: OnceLock<Box<DebugCallback>> = OnceLock::new();
-
-unsafe extern "C" fn debug_trampoline(ty: ESteamNetworkingSocketsDebugOutputType, msg: MsgPtr) {
-    if let Some(cb) = DEBUG_CB.get() {
-        let s = unsafe { CStr::from_ptr(msg) }.to_str().unwrap_or("");
-        cb(ty, s);
-    }
-}
-
 impl GnsUtils {
-    /// Install a debug callback. Subsequent calls are silently ignored —
-    /// only the first registration wins. The callback runs on GNS's service
-    /// thread; the `&str` is borrowed for the call duration only.
-    ///
-    /// The callback may capture state (it is stored as a boxed closure), but
-    /// must therefore be `Send + Sync + 'static` since GNS invokes it from its
-    /// own thread.
-    pub fn enable_debug_output(
-        &self,
-        ty: ESteamNetworkingSocketsDebugOutputType,
-        f: impl Fn(ESteamNetworkingSocketsDebugOutputType, &str) + Send + Sync + 'static,
-    ) {
-        let _ = DEBUG_CB.set(Box::new(f));
-        unsafe {
-            SteamAPI_ISteamNetworkingUtils_SetDebugOutputFunction(
-                get_utils(),
-                ty,
-                Some(debug_trampoline),
-            );
-        }
-    }
-
     /// Allocate a new outbound message, taking ownership of `payload`.
     /// The buffer is held until GNS releases the message, at which point
     /// the wrapper reconstructs `P` via [`Payload::from_raw`] and lets
@@ -0,0 +1,298 @@
+//! Connectionless, identity-addressed messaging over
+//! `ISteamNetworkingMessages` (`p2p` feature).
+//!
+//! GNS opens a session with a peer on the first message exchanged with it,
+//! and closes it after a period of inactivity. Messages are received per
+//! channel rather than per connection, and a peer contacting us for the
+//! first time must be accepted, see [`GnsMessagesEvent::SessionRequest`].
+//!
+//! Sessions are P2P connections: their rendezvous goes through the
+//! application's signaling channel, given with
+//! [`GnsMessages::set_signaling`], and the signals received from peers are
+//! handed to [`GnsGlobal::received_signal`] like for
+//! [`GnsSocket<IsPeer>`](crate::GnsSocket).
+
+use crate::p2p::SignalingObject;
+use crate::sys::*;
+use crate::{
+    check, GnsConnectionInfo, GnsConnectionRealTimeStatus, GnsError, GnsGlobal, GnsIdentity,
+    GnsResult, MessageSlot, ReceivedMessages, ReceivedMessagesInto, SendFlags, SignalingFactory,
+    SlotCursor,
+};
+use crossbeam_queue::SegQueue;
+use std::{
+    ffi::{c_int, c_void},
+    sync::{Once, RwLock},
+};
+
+#[inline]
+fn get_messages() -> *mut ISteamNetworkingMessages {
+    unsafe { SteamAPI_SteamNetworkingMessages_v002() }
+}
+
+/// Session event of [`GnsMessages`], see [`GnsMessages::receive_events`].
+#[derive(Debug, Clone)]
+pub enum GnsMessagesEvent {
+    /// A peer without a session sent us a message. Its messages are only
+    /// delivered once [`GnsMessages::accept_session`] is called, otherwise
+    /// the request times out.
+    SessionRequest(GnsIdentity),
+    /// A session could not be established or broke; the info carries the
+    /// remote identity and end reason.
+    SessionFailed(Box<GnsConnectionInfo>),
+}
+
+/// The `ISteamNetworkingMessages` interface, see [`GnsGlobal::messages`].
+pub struct GnsMessages {
+    events: SegQueue<GnsMessagesEvent>,
+    callbacks: Once,
+    /// See [`GnsMessages::set_signaling`].
+    signaling: RwLock<Option<Box<dyn SignalingFactory>>>,
+}
+
+impl GnsMessages {
+    pub(crate) fn new() -> Self {
+        GnsMessages {
+            events: SegQueue::new(),
+            callbacks: Once::new(),
+            signaling: RwLock::new(None),
+        }
+    }
+
+    unsafe extern "C" fn on_session_request(request: &mut SteamNetworkingMessagesSessionRequest_t) {
+        // GnsGlobal needs to be initialized to even reach this point in the first place.
+        let gns_global = GnsGlobal::get().expect("GnsGlobal should be initialized");
+        gns_global
+            .messages
+            .events
+            .push(GnsMessagesEvent::SessionRequest(GnsIdentity(
+                request.m_identityRemote,
+            )));
+    }
+
+    unsafe extern "C" fn on_session_failed(failure: &mut SteamNetworkingMessagesSessionFailed_t) {
+        let gns_global = GnsGlobal::get().expect("GnsGlobal should be initialized");
+        gns_global
+            .messages
+            .events
+            .push(GnsMessagesEvent::SessionFailed(Box::new(
+                GnsConnectionInfo(failure.m_info),
+            )));
+    }
+
+    unsafe extern "C" fn on_create_signaling(
+        _: *mut ISteamNetworkingSockets,
+        peer: &SteamNetworkingIdentity,
+        local_virtual_port: c_int,
+        remote_virtual_port: c_int,
+    ) -> *mut ISteamNetworkingConnectionSignaling {
+        let gns_global = GnsGlobal::get().expect("GnsGlobal should be initialized");
+        let signaling = gns_global.messages.signaling.read().unwrap();
+        signaling
+            .as_ref()
+            .and_then(|factory| {
+                factory.create_signaling(
+                    &GnsIdentity(*peer),
+                    local_virtual_port,
+                    remote_virtual_port,
+                )
+            })
+            .map_or(core::ptr::null_mut(), SignalingObject::into_raw)
+    }
+
+    /// Route the session callbacks, dispatched by
+    /// [`GnsGlobal::poll_callbacks`], to the event queue, and the signaling
+    /// of new sessions to [`GnsMessages::set_signaling`].
+    fn register_callbacks(&self) {
+        self.callbacks.call_once(|| unsafe {
+            SteamAPI_ISteamNetworkingUtils_SetGlobalConfigValuePtr(
+                crate::get_utils(),
+                ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Callback_MessagesSessionRequest,
+                Self::on_session_request
+                    as *const fn(&SteamNetworkingMessagesSessionRequest_t)
+                    as *mut c_void,
+            );
+            SteamAPI_ISteamNetworkingUtils_SetGlobalConfigValuePtr(
+                crate::get_utils(),
+                ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Callback_MessagesSessionFailed,
+                Self::on_session_failed as *const fn(&SteamNetworkingMessagesSessionFailed_t)
+                    as *mut c_void,
+            );
+            SteamAPI_ISteamNetworkingUtils_SetGlobalConfigValuePtr(
+                crate::get_utils(),
+                ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_Callback_CreateConnectionSignaling,
+                Self::on_create_signaling as *mut c_void,
+            );
+        });
+    }
+
+    /// Open the sessions with peers through `factory`, asked for the
+    /// signaling channel of each session GNS starts, replacing the previous
+    /// one. Until set, sessions fail to start.
+    pub fn set_signaling(&self, factory: impl SignalingFactory) {
+        *self.signaling.write().unwrap() = Some(Box::new(factory));
+    }
+
+    /// Send `payload` to `identity` on its `channel`, opening a session with
+    /// it if needed. The payload is copied.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with the result of GNS, e.g.
+    /// `k_EResultNoConnection` if the session failed and
+    /// [`SendFlags::AUTO_RESTART_BROKEN_SESSION`] was not given.
+    pub fn send_message_to_user(
+        &self,
+        identity: &GnsIdentity,
+        flags: SendFlags,
+        channel: i32,
+        payload: &[u8],
+    ) -> GnsResult<()> {
+        let result = check(unsafe {
+            SteamAPI_ISteamNetworkingMessages_SendMessageToUser(
+                get_messages(),
+                &identity.0,
+                payload.as_ptr() as _,
+                payload.len() as _,
+                flags.bits(),
+                channel,
+            )
+        });
+        #[cfg(feature = "metrics")]
+        match result {
+            Err(GnsError::Api(e)) => crate::sampler::record_send_failure(e),
+            _ => crate::sampler::record_sent(1),
+        }
+        result
+    }
+
+    fn receive(&self, channel: i32, slots: &mut [MessageSlot]) -> GnsResult<usize> {
+        let result = unsafe {
+            SteamAPI_ISteamNetworkingMessages_ReceiveMessagesOnChannel(
+                get_messages(),
+                channel,
+                slots.as_mut_ptr() as _,
+                slots.len() as _,
+            )
+        };
+        let count = usize::try_from(result).map_err(|_| GnsError::Receive)?;
+        #[cfg(feature = "metrics")]
+        crate::sampler::record_received(count);
+        Ok(count)
+    }
+
+    /// Receive up to `K` messages sent to `channel` by any peer, see
+    /// [`GnsSocket::receive_messages`](crate::GnsSocket::receive_messages).
+    /// The sender of each is given by
+    /// [`GnsNetworkMessage::peer_identity`](crate::GnsNetworkMessage::peer_identity).
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Receive`] if GNS reports a failure.
+    pub fn receive_messages_on_channel<const K: usize>(
+        &self,
+        channel: i32,
+    ) -> GnsResult<ReceivedMessages<K>> {
+        let mut slots: [MessageSlot; K] = [const { MessageSlot::uninit() }; K];
+        let len = self.receive(channel, &mut slots)?;
+        Ok(ReceivedMessages {
+            slots,
+            cursor: SlotCursor { len, pos: 0 },
+        })
+    }
+
+    /// Receive up to `buffer.len()` messages sent to `channel` into a
+    /// caller-owned `buffer`, see
+    /// [`GnsSocket::receive_messages_into`](crate::GnsSocket::receive_messages_into).
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Receive`] if GNS reports a failure.
+    pub fn receive_messages_on_channel_into<'a>(
+        &self,
+        channel: i32,
+        buffer: &'a mut [MessageSlot],
+    ) -> GnsResult<ReceivedMessagesInto<'a>> {
+        let len = self.receive(channel, buffer)?;
+        Ok(ReceivedMessagesInto {
+            slots: buffer,
+            cursor: SlotCursor { len, pos: 0 },
+        })
+    }
+
+    /// Drain the pending session events. Like connection events, they are
+    /// only raised by [`GnsGlobal::poll_callbacks`].
+    pub fn receive_events(&self) -> impl Iterator<Item = GnsMessagesEvent> + '_ {
+        core::iter::from_fn(|| self.events.pop())
+    }
+
+    /// Accept the session requested by `identity`, delivering its messages.
+    /// Sending a message to the peer accepts the session as well.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Session`] if `identity` has no pending request.
+    pub fn accept_session(&self, identity: &GnsIdentity) -> GnsResult<()> {
+        unsafe {
+            SteamAPI_ISteamNetworkingMessages_AcceptSessionWithUser(get_messages(), &identity.0)
+        }
+        .then_some(())
+        .ok_or(GnsError::Session)
+    }
+
+    /// Close the session with `identity`, dropping the messages it still
+    /// has in flight.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Session`] if there is no session with `identity`.
+    pub fn close_session(&self, identity: &GnsIdentity) -> GnsResult<()> {
+        unsafe {
+            SteamAPI_ISteamNetworkingMessages_CloseSessionWithUser(get_messages(), &identity.0)
+        }
+        .then_some(())
+        .ok_or(GnsError::Session)
+    }
+
+    /// Stop receiving on `channel` from `identity`. The session is closed
+    /// once all its channels are.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Session`] if there is no session with `identity`.
+    pub fn close_channel(&self, identity: &GnsIdentity, channel: i32) -> GnsResult<()> {
+        unsafe {
+            SteamAPI_ISteamNetworkingMessages_CloseChannelWithUser(
+                get_messages(),
+                &identity.0,
+                channel,
+            )
+        }
+        .then_some(())
+        .ok_or(GnsError::Session)
+    }
+
+    /// Info and real-time status of the session with `identity`, `None` if
+    /// there is none.
+    pub fn session_info(
+        &self,
+        identity: &GnsIdentity,
+    ) -> Option<(GnsConnectionInfo, GnsConnectionRealTimeStatus)> {
+        let mut info = GnsConnectionInfo::default();
+        let mut status = GnsConnectionRealTimeStatus::default();
+        let state = unsafe {
+            SteamAPI_ISteamNetworkingMessages_GetSessionConnectionInfo(
+                get_messages(),
+                &identity.0,
+                &mut info.0,
+                &mut status.0,
+            )
+        };
+        (state != ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None)
+            .then_some((info, status))
+    }
+}
+
+impl GnsGlobal {
+    /// The connectionless messaging interface. Its session events are
+    /// queued from the first call on.
+    #[inline]
+    pub fn messages(&self) -> &GnsMessages {
+        self.messages.register_callbacks();
+        &self.messages
+    }
+}
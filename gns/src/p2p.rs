@@ -0,0 +1,490 @@
+//! Peer-to-peer connections over ICE with custom signaling (`p2p` feature).
+//!
+//! Before a P2P connection can carry data, both ends exchange rendezvous
+//! messages (connect requests, ICE candidates...) over a channel provided by
+//! the application. Outgoing signals are handed to a [`Signaling`] object,
+//! one per connection; incoming ones are fed back to GNS with
+//! [`GnsGlobal::received_signal`]. [`LocalSignaling`] is an in-process
+//! channel, e.g. for tests.
+//!
+//! The sessions of [`GnsMessages`](crate::GnsMessages) are P2P connections
+//! too, opened by GNS itself: their signaling comes from the
+//! [`SignalingFactory`] given to
+//! [`GnsMessages::set_signaling`](crate::GnsMessages::set_signaling).
+//!
+//! The signaling objects are handed to GNS as C++ objects, laid out here
+//! following the Itanium and x64 MSVC ABIs. 32-bit MSVC targets, whose
+//! virtual calls use `thiscall`, are not supported.
+
+use crate::sys::*;
+use crate::{
+    config::ConfigOption, get_interface, GnsConnection, GnsConnectionEvent, GnsConnectionInfo,
+    GnsError, GnsGlobal, GnsIdentity, GnsListenSocket, GnsPollGroup, GnsResult, GnsSocket,
+    IsCreated, IsReady, MessageSlot, Route,
+};
+use crossbeam_queue::SegQueue;
+use std::{
+    ffi::{c_int, c_void},
+    sync::{Arc, Mutex},
+};
+
+/// Outgoing half of the signaling channel of one P2P connection.
+///
+/// GNS may call it from any of its threads, and expects it to return
+/// quickly: queue the signal rather than doing blocking I/O.
+pub trait Signaling: Send + Sync + 'static {
+    /// Deliver `signal` to the peer at the other end of `connection`, where
+    /// it must be passed to [`GnsGlobal::received_signal`]. Signals may be
+    /// dropped or reordered, GNS retries as needed. Returning `false`
+    /// reports that the channel is broken, failing the connection.
+    fn send_signal(
+        &self,
+        connection: GnsConnection,
+        info: &GnsConnectionInfo,
+        signal: &[u8],
+    ) -> bool;
+}
+
+/// Handles the signals of [`GnsGlobal::received_signal`] that do not belong
+/// to an existing connection.
+pub trait SignalingRecvContext {
+    /// A peer requests a new connection to a local [`GnsSocket<IsPeer>`]
+    /// listening on `local_virtual_port`. Return the [`Signaling`] to answer
+    /// it with, or `None` to ignore the request. An accepted request shows up
+    /// as a [`ConnectionTransition::IncomingRequest`](crate::ConnectionTransition::IncomingRequest)
+    /// event on the peer socket.
+    fn on_connect_request(
+        &mut self,
+        connection: GnsConnection,
+        peer: &GnsIdentity,
+        local_virtual_port: i32,
+    ) -> Option<Box<dyn Signaling>>;
+
+    /// Send `signal` to `peer` to reject its connection attempt. By default
+    /// nothing is sent and the peer times out.
+    fn send_rejection_signal(&mut self, peer: &GnsIdentity, signal: &[u8]) {
+        let _ = (peer, signal);
+    }
+}
+
+/// Creates the [`Signaling`] of the P2P connections GNS opens by itself,
+/// i.e. the sessions of [`GnsMessages`](crate::GnsMessages).
+pub trait SignalingFactory: Send + Sync + 'static {
+    /// The signaling channel to reach `peer` through, or `None` if it can't
+    /// be reached, failing the session. GNS calls it with its lock held, so
+    /// it must not call back into GNS.
+    fn create_signaling(
+        &self,
+        peer: &GnsIdentity,
+        local_virtual_port: i32,
+        remote_virtual_port: i32,
+    ) -> Option<Box<dyn Signaling>>;
+}
+
+/// Borrow the `len` bytes GNS passed at `signal`, possibly null when empty.
+unsafe fn signal_slice<'a>(signal: *const c_void, len: c_int) -> &'a [u8] {
+    if signal.is_null() || len <= 0 {
+        &[]
+    } else {
+        unsafe { core::slice::from_raw_parts(signal as *const u8, len as _) }
+    }
+}
+
+/// Virtual table of `ISteamNetworkingConnectionSignaling`.
+#[repr(C)]
+struct SignalingVTable {
+    send_signal: unsafe extern "C" fn(
+        *mut SignalingObject,
+        HSteamNetConnection,
+        *const SteamNetConnectionInfo_t,
+        *const c_void,
+        c_int,
+    ) -> bool,
+    release: unsafe extern "C" fn(*mut SignalingObject),
+}
+
+/// `ISteamNetworkingConnectionSignaling` forwarding to a [`Signaling`],
+/// owned by GNS until it calls `Release`.
+#[repr(C)]
+pub(crate) struct SignalingObject {
+    vtable: &'static SignalingVTable,
+    signaling: Box<dyn Signaling>,
+}
+
+static SIGNALING_VTABLE: SignalingVTable = SignalingVTable {
+    send_signal: SignalingObject::send_signal,
+    release: SignalingObject::release,
+};
+
+impl SignalingObject {
+    pub(crate) fn into_raw(
+        signaling: Box<dyn Signaling>,
+    ) -> *mut ISteamNetworkingConnectionSignaling {
+        Box::into_raw(Box::new(SignalingObject {
+            vtable: &SIGNALING_VTABLE,
+            signaling,
+        })) as _
+    }
+
+    unsafe extern "C" fn send_signal(
+        this: *mut SignalingObject,
+        connection: HSteamNetConnection,
+        info: *const SteamNetConnectionInfo_t,
+        signal: *const c_void,
+        len: c_int,
+    ) -> bool {
+        let signal = unsafe { signal_slice(signal, len) };
+        unsafe { &*this }.signaling.send_signal(
+            GnsConnection(connection),
+            &GnsConnectionInfo(unsafe { *info }),
+            signal,
+        )
+    }
+
+    unsafe extern "C" fn release(this: *mut SignalingObject) {
+        drop(unsafe { Box::from_raw(this) });
+    }
+}
+
+/// Virtual table of `ISteamNetworkingSignalingRecvContext`.
+#[repr(C)]
+struct RecvContextVTable {
+    on_connect_request: unsafe extern "C" fn(
+        *mut c_void,
+        HSteamNetConnection,
+        *const SteamNetworkingIdentity,
+        c_int,
+    ) -> *mut ISteamNetworkingConnectionSignaling,
+    send_rejection_signal:
+        unsafe extern "C" fn(*mut c_void, *const SteamNetworkingIdentity, *const c_void, c_int),
+}
+
+/// `ISteamNetworkingSignalingRecvContext` borrowing a
+/// [`SignalingRecvContext`] for the duration of one
+/// [`GnsGlobal::received_signal`] call.
+#[repr(C)]
+struct RecvContextObject<'a> {
+    vtable: &'static RecvContextVTable,
+    context: &'a mut dyn SignalingRecvContext,
+}
+
+static RECV_CONTEXT_VTABLE: RecvContextVTable = RecvContextVTable {
+    on_connect_request: RecvContextObject::on_connect_request,
+    send_rejection_signal: RecvContextObject::send_rejection_signal,
+};
+
+impl RecvContextObject<'_> {
+    unsafe extern "C" fn on_connect_request(
+        this: *mut c_void,
+        connection: HSteamNetConnection,
+        peer: *const SteamNetworkingIdentity,
+        local_virtual_port: c_int,
+    ) -> *mut ISteamNetworkingConnectionSignaling {
+        let this = unsafe { &mut *(this as *mut RecvContextObject) };
+        this.context
+            .on_connect_request(
+                GnsConnection(connection),
+                &GnsIdentity(unsafe { *peer }),
+                local_virtual_port,
+            )
+            .map_or(core::ptr::null_mut(), SignalingObject::into_raw)
+    }
+
+    unsafe extern "C" fn send_rejection_signal(
+        this: *mut c_void,
+        peer: *const SteamNetworkingIdentity,
+        signal: *const c_void,
+        len: c_int,
+    ) {
+        let this = unsafe { &mut *(this as *mut RecvContextObject) };
+        let signal = unsafe { signal_slice(signal, len) };
+        this.context
+            .send_rejection_signal(&GnsIdentity(unsafe { *peer }), signal);
+    }
+}
+
+impl GnsGlobal {
+    /// Hand a signal received from the signaling channel to GNS. Signals for
+    /// existing connections are dispatched to them, connect requests go
+    /// through `context`.
+    ///
+    /// Returns `false` if the signal was malformed or could not be handled.
+    pub fn received_signal(&self, signal: &[u8], context: &mut impl SignalingRecvContext) -> bool {
+        let mut context = RecvContextObject {
+            vtable: &RECV_CONTEXT_VTABLE,
+            context,
+        };
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_ReceivedP2PCustomSignal(
+                get_interface(),
+                signal.as_ptr() as _,
+                signal.len() as _,
+                &mut context as *mut RecvContextObject as *mut ISteamNetworkingSignalingRecvContext,
+            )
+        }
+    }
+}
+
+/// State of a [`GnsSocket`] accepting and initiating P2P connections, see
+/// [`GnsSocket::peer`]. Like [`IsServer`](crate::IsServer), its connections
+/// share one poll group and event queue.
+pub struct IsPeer {
+    queue: Arc<SegQueue<GnsConnectionEvent>>,
+    queue_id: i64,
+    global: &'static GnsGlobal,
+    listen_socket: GnsListenSocket,
+    local_virtual_port: i32,
+    poll_group: GnsPollGroup,
+    /// Connections initiated by [`GnsSocket::connect_p2p`], closed on drop.
+    outgoing: Mutex<Vec<GnsConnection>>,
+    /// Options of the socket builder, applied to outgoing connections too.
+    options: Vec<ConfigOption>,
+}
+
+impl Drop for IsPeer {
+    fn drop(&mut self) {
+        unsafe {
+            for connection in self.outgoing.get_mut().unwrap().drain(..) {
+                SteamAPI_ISteamNetworkingSockets_CloseConnection(
+                    get_interface(),
+                    connection.0,
+                    0,
+                    core::ptr::null(),
+                    false,
+                );
+                self.global.unroute(Route::Connection(connection.0));
+            }
+            SteamAPI_ISteamNetworkingSockets_CloseListenSocket(
+                get_interface(),
+                self.listen_socket.0,
+            );
+        }
+        self.global.unroute(Route::Listen(self.listen_socket.0));
+        self.global.remove_queue(self.queue_id);
+    }
+}
+
+impl crate::private::Sealed for IsPeer {
+    #[inline]
+    fn queue_id(&self) -> i64 {
+        self.queue_id
+    }
+
+    #[cfg(feature = "tokio")]
+    #[inline]
+    fn message_source(&self) -> crate::driver::MessageSource {
+        crate::driver::MessageSource::PollGroup(self.poll_group.0)
+    }
+}
+
+impl IsReady for IsPeer {
+    #[inline]
+    fn queue(&self) -> &SegQueue<GnsConnectionEvent> {
+        &self.queue
+    }
+
+    #[inline]
+    fn receive(&self, slots: &mut [MessageSlot]) -> GnsResult<usize> {
+        self.poll_group.receive(slots)
+    }
+}
+
+impl GnsSocket<IsCreated> {
+    /// Accept P2P connections requested to `local_virtual_port`, the socket
+    /// transition from [`IsCreated`] to [`IsPeer`]. Connections to other
+    /// peers are then initiated with [`GnsSocket::connect_p2p`].
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Listen`] if the virtual port is already in use.
+    #[inline]
+    pub fn peer(self, local_virtual_port: i32) -> GnsResult<GnsSocket<IsPeer>> {
+        self.peer_inner(local_virtual_port, Vec::new())
+    }
+
+    pub(crate) fn peer_inner(
+        self,
+        local_virtual_port: i32,
+        options: Vec<ConfigOption>,
+    ) -> GnsResult<GnsSocket<IsPeer>> {
+        let (queue_id, queue) = self.global.create_queue(None);
+        let poll_group = self.global.create_poll_group()?;
+        let raw_options = Self::connection_options(&options);
+        let listen_socket = self
+            .global
+            .create_routed(queue_id, Route::Listen, || {
+                let listen_socket = unsafe {
+                    SteamAPI_ISteamNetworkingSockets_CreateListenSocketP2P(
+                        get_interface(),
+                        local_virtual_port,
+                        raw_options.len() as _,
+                        raw_options.as_ptr(),
+                    )
+                };
+                (listen_socket != k_HSteamListenSocket_Invalid).then_some(listen_socket)
+            })
+            .ok_or(GnsError::Listen)?;
+        Ok(GnsSocket {
+            global: self.global,
+            state: IsPeer {
+                queue,
+                queue_id,
+                global: self.global,
+                listen_socket: GnsListenSocket(listen_socket),
+                local_virtual_port,
+                poll_group,
+                outgoing: Mutex::new(Vec::new()),
+                options,
+            },
+        })
+    }
+}
+
+impl GnsSocket<IsPeer> {
+    /// Start a P2P connection to the peer socket `identity` listens with on
+    /// `remote_virtual_port`, rendezvousing through `signaling`. The
+    /// connection is attached to the poll group of this socket.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Connect`] if GNS refuses the connection, e.g.
+    /// when `identity` is invalid.
+    pub fn connect_p2p(
+        &self,
+        identity: &GnsIdentity,
+        remote_virtual_port: i32,
+        signaling: impl Signaling,
+    ) -> GnsResult<GnsConnection> {
+        let options = GnsSocket::<IsCreated>::connection_options(&self.state.options);
+        let connection = self
+            .global
+            .create_routed(self.state.queue_id, Route::Connection, || {
+                // GNS owns the signaling object from here on, even on failure.
+                let connection = unsafe {
+                    SteamAPI_ISteamNetworkingSockets_ConnectP2PCustomSignaling(
+                        get_interface(),
+                        SignalingObject::into_raw(Box::new(signaling)),
+                        &identity.0,
+                        remote_virtual_port,
+                        options.len() as _,
+                        options.as_ptr(),
+                    )
+                };
+                (connection != k_HSteamNetConnection_Invalid).then_some(connection)
+            })
+            .ok_or(GnsError::Connect)?;
+        let connection = GnsConnection(connection);
+        {
+            let mut outgoing = self.state.outgoing.lock().unwrap();
+            // Forget the connections closed since, their handles are invalid.
+            outgoing.retain(|&c| {
+                let open = self.get_connection_info(c).is_some();
+                if !open {
+                    self.global.unroute(Route::Connection(c.0));
+                }
+                open
+            });
+            outgoing.push(connection);
+        }
+        self.state.poll_group.attach(connection)?;
+        Ok(connection)
+    }
+
+    /// Accept an incoming P2P connection, attaching it to the poll group of
+    /// this socket.
+    pub fn accept(&self, connection: GnsConnection) -> GnsResult<()> {
+        crate::check(unsafe {
+            SteamAPI_ISteamNetworkingSockets_AcceptConnection(get_interface(), connection.0)
+        })?;
+        self.state.poll_group.attach(connection)
+    }
+
+    /// The poll group the connections of this socket are attached to.
+    #[inline]
+    pub fn poll_group(&self) -> &GnsPollGroup {
+        &self.state.poll_group
+    }
+
+    /// The listen socket incoming connections arrive on.
+    #[inline]
+    pub fn listen_socket(&self) -> GnsListenSocket {
+        self.state.listen_socket
+    }
+
+    #[inline]
+    pub fn local_virtual_port(&self) -> i32 {
+        self.state.local_virtual_port
+    }
+}
+
+/// In-process signaling channel between two ends, both usually in the same
+/// process: what one end sends is queued for the other until it calls
+/// [`LocalSignaling::deliver`]. Requires no network service, which makes it
+/// suited to tests.
+///
+/// Each end is both the [`Signaling`] of the connections it carries, the
+/// [`SignalingRecvContext`] answering connect requests and the
+/// [`SignalingFactory`] of the sessions it carries.
+#[derive(Clone)]
+pub struct LocalSignaling {
+    inbox: Arc<SegQueue<Vec<u8>>>,
+    outbox: Arc<SegQueue<Vec<u8>>>,
+}
+
+impl LocalSignaling {
+    /// Create the two ends of a channel.
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = (Arc::new(SegQueue::new()), Arc::new(SegQueue::new()));
+        (
+            LocalSignaling {
+                inbox: a.clone(),
+                outbox: b.clone(),
+            },
+            LocalSignaling {
+                inbox: b,
+                outbox: a,
+            },
+        )
+    }
+
+    /// Hand the signals queued for this end to GNS, returning their count.
+    pub fn deliver(&self, global: &GnsGlobal) -> usize {
+        let mut context = self.clone();
+        let mut count = 0;
+        while let Some(signal) = self.inbox.pop() {
+            global.received_signal(&signal, &mut context);
+            count += 1;
+        }
+        count
+    }
+}
+
+impl Signaling for LocalSignaling {
+    #[inline]
+    fn send_signal(&self, _: GnsConnection, _: &GnsConnectionInfo, signal: &[u8]) -> bool {
+        self.outbox.push(signal.to_vec());
+        true
+    }
+}
+
+impl SignalingRecvContext for LocalSignaling {
+    #[inline]
+    fn on_connect_request(
+        &mut self,
+        _: GnsConnection,
+        _: &GnsIdentity,
+        _: i32,
+    ) -> Option<Box<dyn Signaling>> {
+        Some(Box::new(self.clone()))
+    }
+
+    #[inline]
+    fn send_rejection_signal(&mut self, _: &GnsIdentity, signal: &[u8]) {
+        self.outbox.push(signal.to_vec());
+    }
+}
+
+impl SignalingFactory for LocalSignaling {
+    #[inline]
+    fn create_signaling(&self, _: &GnsIdentity, _: i32, _: i32) -> Option<Box<dyn Signaling>> {
+        Some(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,340 @@
+//! Monitoring of the quality of connections.
+//!
+//! [`QualityMonitor`] samples the real-time status of the connections of a
+//! socket, averages each value over a rolling window, and raises a
+//! [`QualityEvent`] when an average crosses its threshold. An issue only
+//! recovers once its average falls well under the threshold, so a value
+//! hovering around it doesn't flap.
+
+use crate::{GnsConnection, GnsConnectionRealTimeStatus, GnsSocket, IsReady};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Thresholds of the [`QualityMonitor`], compared to the averages of the
+/// window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityThresholds {
+    /// Round-trip time in milliseconds.
+    pub ping_ms: u32,
+    /// Percentage of packets dropped.
+    pub packet_loss_pct: f32,
+    /// Jitter in milliseconds.
+    pub jitter_ms: f32,
+    /// Time a message queued now waits before being sent.
+    pub queue_time: Duration,
+    /// An issue recovers once its average falls under this fraction of its
+    /// threshold, between 0 and 1.
+    pub recovery_ratio: f32,
+}
+
+impl Default for QualityThresholds {
+    fn default() -> Self {
+        QualityThresholds {
+            ping_ms: 150,
+            packet_loss_pct: 5.0,
+            jitter_ms: 30.0,
+            queue_time: Duration::from_millis(200),
+            recovery_ratio: 0.8,
+        }
+    }
+}
+
+/// Values of a connection at one point in time, see
+/// [`QualityMonitor::observe`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct QualitySample {
+    pub ping_ms: u32,
+    /// `None` while GNS doesn't know it yet.
+    pub packet_loss_pct: Option<f32>,
+    /// `None` if the connection doesn't measure it.
+    pub jitter_ms: Option<f32>,
+    pub queue_time: Duration,
+}
+
+impl From<&GnsConnectionRealTimeStatus> for QualitySample {
+    fn from(status: &GnsConnectionRealTimeStatus) -> Self {
+        // The quality is negative while unknown.
+        let quality = status.quality_local();
+        QualitySample {
+            ping_ms: status.ping(),
+            packet_loss_pct: (quality >= 0.0).then_some((1.0 - quality) * 100.0),
+            jitter_ms: status.max_jitter_usec().map(|usec| usec as f32 / 1000.0),
+            queue_time: status.approximated_queue_time(),
+        }
+    }
+}
+
+/// Kind of threshold a connection crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityIssue {
+    HighPing,
+    PacketLoss,
+    Jitter,
+    QueueBackedUp,
+}
+
+/// Change of the quality of a connection, see [`QualityMonitor::poll`]. The
+/// values are the averages of the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QualityEvent {
+    HighPing {
+        connection: GnsConnection,
+        ping_ms: u32,
+    },
+    PacketLossSpike {
+        connection: GnsConnection,
+        packet_loss_pct: f32,
+    },
+    JitterSpike {
+        connection: GnsConnection,
+        jitter_ms: f32,
+    },
+    QueueBackedUp {
+        connection: GnsConnection,
+        queue_time: Duration,
+    },
+    /// The average of `issue` fell back under its recovery threshold.
+    Recovered {
+        connection: GnsConnection,
+        issue: QualityIssue,
+    },
+}
+
+impl QualityEvent {
+    #[inline]
+    pub fn connection(&self) -> GnsConnection {
+        match *self {
+            QualityEvent::HighPing { connection, .. }
+            | QualityEvent::PacketLossSpike { connection, .. }
+            | QualityEvent::JitterSpike { connection, .. }
+            | QualityEvent::QueueBackedUp { connection, .. }
+            | QualityEvent::Recovered { connection, .. } => connection,
+        }
+    }
+}
+
+/// Rolling window and raised issues of a connection.
+#[derive(Default)]
+struct ConnectionQuality {
+    samples: VecDeque<QualitySample>,
+    issues: Vec<QualityIssue>,
+}
+
+impl ConnectionQuality {
+    fn average(&self) -> Option<QualitySample> {
+        let len = self.samples.len();
+        if len == 0 {
+            return None;
+        }
+        let mean = |values: Vec<f32>| {
+            (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+        };
+        Some(QualitySample {
+            ping_ms: (self.samples.iter().map(|s| s.ping_ms as u64).sum::<u64>() / len as u64)
+                as u32,
+            packet_loss_pct: mean(
+                self.samples
+                    .iter()
+                    .filter_map(|s| s.packet_loss_pct)
+                    .collect(),
+            ),
+            jitter_ms: mean(self.samples.iter().filter_map(|s| s.jitter_ms).collect()),
+            queue_time: self.samples.iter().map(|s| s.queue_time).sum::<Duration>() / len as u32,
+        })
+    }
+
+    /// Raise `issue` once `value` exceeds `threshold`, recover it once
+    /// `value` falls under `recovery`. `None` while the state is unchanged,
+    /// and when the value is unknown.
+    fn update(
+        &mut self,
+        issue: QualityIssue,
+        value: Option<f32>,
+        threshold: f32,
+        recovery: f32,
+    ) -> Option<bool> {
+        let value = value?;
+        let raised = self.issues.contains(&issue);
+        if !raised && value > threshold {
+            self.issues.push(issue);
+            Some(true)
+        } else if raised && value < recovery {
+            self.issues.retain(|&i| i != issue);
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Monitor of the quality of the connections of a [`GnsSocket`], e.g.
+///
+/// ```no_run
+/// # use gns::{GnsGlobal, GnsSocket, QualityEvent, QualityMonitor, QualityThresholds};
+/// # use std::net::Ipv4Addr;
+/// let gns_global = GnsGlobal::get().unwrap();
+/// let server = GnsSocket::new(gns_global)
+///     .listen(Ipv4Addr::UNSPECIFIED.into(), 27015)
+///     .unwrap();
+/// let mut monitor = QualityMonitor::new(QualityThresholds::default());
+/// loop {
+///     gns_global.poll_callbacks();
+///     for event in monitor.poll(&server) {
+///         if let QualityEvent::HighPing { connection, ping_ms } = event {
+///             println!("{connection:?} lags with {ping_ms}ms");
+///         }
+///     }
+///     # break;
+/// }
+/// ```
+pub struct QualityMonitor {
+    thresholds: QualityThresholds,
+    interval: Duration,
+    window: usize,
+    last_poll: Option<Instant>,
+    connections: HashMap<GnsConnection, ConnectionQuality>,
+}
+
+impl QualityMonitor {
+    /// Monitor sampling every second, averaging over the last 5 samples.
+    pub fn new(thresholds: QualityThresholds) -> Self {
+        QualityMonitor {
+            thresholds,
+            interval: Duration::from_secs(1),
+            window: 5,
+            last_poll: None,
+            connections: HashMap::new(),
+        }
+    }
+
+    /// Sample at most once every `interval`.
+    #[inline]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Average over the last `samples` samples, at least one.
+    #[inline]
+    pub fn with_window(mut self, samples: usize) -> Self {
+        self.window = samples.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn thresholds(&self) -> &QualityThresholds {
+        &self.thresholds
+    }
+
+    /// Sample the connections of `socket`, see [`GnsSocket::connections`],
+    /// if the interval elapsed since the last sampling, and return the
+    /// events raised. Connections gone from the socket are forgotten.
+    ///
+    /// Reading the real-time status resets the jitter high water mark of
+    /// GNS, see [`GnsSocket::get_connection_real_time_status`]: each sample
+    /// holds the highest jitter since the status was last read, by this
+    /// monitor or any other caller.
+    pub fn poll<S: IsReady>(&mut self, socket: &GnsSocket<S>) -> Vec<QualityEvent> {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last| now.duration_since(last) < self.interval)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        let connections = socket.connections();
+        self.connections.retain(|c, _| connections.contains(c));
+        let mut events = Vec::new();
+        for connection in connections {
+            if let Ok((status, _)) = socket.get_connection_real_time_status(connection, 0) {
+                events.extend(self.observe(connection, QualitySample::from(&status)));
+            }
+        }
+        events
+    }
+
+    /// Add `sample` to the window of `connection`, and return the events
+    /// raised.
+    pub fn observe(
+        &mut self,
+        connection: GnsConnection,
+        sample: QualitySample,
+    ) -> Vec<QualityEvent> {
+        let thresholds = self.thresholds;
+        let quality = self.connections.entry(connection).or_default();
+        if quality.samples.len() == self.window {
+            quality.samples.pop_front();
+        }
+        quality.samples.push_back(sample);
+        let average = quality.average().expect("a sample was just pushed; qed;");
+
+        let ratio = thresholds.recovery_ratio;
+        let checks = [
+            (
+                QualityIssue::HighPing,
+                Some(average.ping_ms as f32),
+                thresholds.ping_ms as f32,
+            ),
+            (
+                QualityIssue::PacketLoss,
+                average.packet_loss_pct,
+                thresholds.packet_loss_pct,
+            ),
+            (
+                QualityIssue::Jitter,
+                average.jitter_ms,
+                thresholds.jitter_ms,
+            ),
+            (
+                QualityIssue::QueueBackedUp,
+                Some(average.queue_time.as_secs_f32()),
+                thresholds.queue_time.as_secs_f32(),
+            ),
+        ];
+        checks
+            .into_iter()
+            .filter_map(|(issue, value, threshold)| {
+                let raised = quality.update(issue, value, threshold, threshold * ratio)?;
+                Some(match (raised, issue) {
+                    (false, issue) => QualityEvent::Recovered { connection, issue },
+                    (true, QualityIssue::HighPing) => QualityEvent::HighPing {
+                        connection,
+                        ping_ms: average.ping_ms,
+                    },
+                    (true, QualityIssue::PacketLoss) => QualityEvent::PacketLossSpike {
+                        connection,
+                        packet_loss_pct: average.packet_loss_pct.unwrap_or_default(),
+                    },
+                    (true, QualityIssue::Jitter) => QualityEvent::JitterSpike {
+                        connection,
+                        jitter_ms: average.jitter_ms.unwrap_or_default(),
+                    },
+                    (true, QualityIssue::QueueBackedUp) => QualityEvent::QueueBackedUp {
+                        connection,
+                        queue_time: average.queue_time,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Averages of the window of `connection`, e.g. for a quality indicator,
+    /// `None` if it wasn't sampled yet.
+    pub fn average(&self, connection: GnsConnection) -> Option<QualitySample> {
+        self.connections.get(&connection)?.average()
+    }
+
+    /// Issues of `connection` raised and not recovered yet.
+    pub fn issues(&self, connection: GnsConnection) -> &[QualityIssue] {
+        self.connections
+            .get(&connection)
+            .map_or(&[], |quality| &quality.issues)
+    }
+
+    /// Forget `connection`, e.g. once closed, without waiting for the next
+    /// [`QualityMonitor::poll`].
+    pub fn remove(&mut self, connection: GnsConnection) {
+        self.connections.remove(&connection);
+    }
+}
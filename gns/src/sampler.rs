@@ -0,0 +1,216 @@
+//! Export of the connection statistics to the `metrics` facade.
+//!
+//! [`GnsMetricsSampler`] reports the real-time status of the connections of
+//! a socket as gauges, labeled by `connection`, its raw handle, and for lane
+//! queues by `lane`.
+//! The wrapper itself counts the messages sent and received, and the sends
+//! GNS rejected by `result`:
+//! - `gns_messages_sent_total`
+//! - `gns_messages_received_total`
+//! - `gns_send_failures_total`
+
+use crate::sys::*;
+use crate::{GnsConnection, GnsSocket, IsReady, SendOutcome};
+
+/// Count the messages of `outcomes` sent, and the failed ones by result.
+pub(crate) fn record_outcomes(outcomes: &[SendOutcome]) {
+    let sent = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, SendOutcome::Sent(_)))
+        .count();
+    record_sent(sent);
+    for outcome in outcomes {
+        if let SendOutcome::Failed(result, _) = outcome {
+            record_send_failure(*result);
+        }
+    }
+}
+
+/// Count `count` messages sent.
+pub(crate) fn record_sent(count: usize) {
+    ::metrics::counter!("gns_messages_sent_total").increment(count as u64);
+}
+
+/// Count a send GNS rejected with `result`.
+pub(crate) fn record_send_failure(result: EResult) {
+    let result = format!("{result:?}");
+    let result = result.trim_start_matches("k_EResult").to_owned();
+    ::metrics::counter!("gns_send_failures_total", "result" => result).increment(1);
+}
+
+/// Count `count` messages received.
+pub(crate) fn record_received(count: usize) {
+    ::metrics::counter!("gns_messages_received_total").increment(count as u64);
+}
+
+/// Gauges reported by [`GnsMetricsSampler::sample_connection`], labeled by
+/// `connection`.
+const CONNECTION_GAUGES: [&str; 13] = [
+    "gns_connection_ping_ms",
+    "gns_connection_quality_local",
+    "gns_connection_quality_remote",
+    "gns_connection_in_bytes_per_sec",
+    "gns_connection_out_bytes_per_sec",
+    "gns_connection_in_packets_per_sec",
+    "gns_connection_out_packets_per_sec",
+    "gns_connection_send_rate_bytes_per_sec",
+    "gns_connection_pending_reliable_bytes",
+    "gns_connection_pending_unreliable_bytes",
+    "gns_connection_sent_unacked_reliable_bytes",
+    "gns_connection_queue_time_seconds",
+    "gns_connection_max_jitter_ms",
+];
+
+/// Gauges reported by [`GnsMetricsSampler::sample_connection`], labeled by
+/// `connection` and `lane`.
+const LANE_GAUGES: [&str; 4] = [
+    "gns_lane_pending_reliable_bytes",
+    "gns_lane_pending_unreliable_bytes",
+    "gns_lane_sent_unacked_reliable_bytes",
+    "gns_lane_queue_time_seconds",
+];
+
+/// Sampler of the connection statistics, e.g. run every few seconds next
+/// to [`GnsGlobal::poll_callbacks`](crate::GnsGlobal::poll_callbacks):
+///
+/// ```no_run
+/// # use gns::{GnsGlobal, GnsMetricsSampler, GnsSocket};
+/// # use std::net::Ipv4Addr;
+/// let gns_global = GnsGlobal::get().unwrap();
+/// let server = GnsSocket::new(gns_global)
+///     .listen(Ipv4Addr::UNSPECIFIED.into(), 27015)
+///     .unwrap();
+/// let sampler = GnsMetricsSampler::new().with_lanes(2);
+/// sampler.sample(&server);
+/// ```
+///
+/// The gauges of a connection keep the value last reported before it goes
+/// away, until zeroed with [`GnsMetricsSampler::forget`] or expired by the
+/// exporter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GnsMetricsSampler {
+    nb_of_lanes: u32,
+}
+
+impl GnsMetricsSampler {
+    /// Sampler of the connection statistics only.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also report the queues of the first `nb_of_lanes` lanes of each
+    /// connection. Connections with fewer lanes only report their own
+    /// statistics.
+    #[inline]
+    pub fn with_lanes(self, nb_of_lanes: u32) -> Self {
+        GnsMetricsSampler { nb_of_lanes }
+    }
+
+    /// Report the statistics of every connection of `socket`, see
+    /// [`GnsSocket::connections`]. Returns the number of connections
+    /// reported; the ones GNS has no status for, e.g. closing ones, are
+    /// skipped. Resets the jitter high water marks, see
+    /// [`GnsMetricsSampler::sample_connection`].
+    pub fn sample<S: IsReady>(&self, socket: &GnsSocket<S>) -> usize {
+        socket
+            .connections()
+            .into_iter()
+            .filter(|&connection| self.sample_connection(socket, connection))
+            .count()
+    }
+
+    /// Report the statistics of `connection`, `false` if GNS has no status
+    /// for it.
+    ///
+    /// Reading the real-time status resets the jitter high water mark of
+    /// GNS, see [`GnsSocket::get_connection_real_time_status`]: the reported
+    /// jitter is the highest since the status was last read, by this
+    /// sampler or any other caller.
+    pub fn sample_connection<S: IsReady>(
+        &self,
+        socket: &GnsSocket<S>,
+        connection: GnsConnection,
+    ) -> bool {
+        // GNS rejects a status request for more lanes than configured.
+        let Ok((status, lanes)) = socket
+            .get_connection_real_time_status(connection, self.nb_of_lanes)
+            .or_else(|_| socket.get_connection_real_time_status(connection, 0))
+        else {
+            return false;
+        };
+        let id = connection.into_raw().to_string();
+        macro_rules! gauge {
+            ($name:literal, $value:expr) => {
+                ::metrics::gauge!($name, "connection" => id.clone()).set($value as f64)
+            };
+        }
+        gauge!("gns_connection_ping_ms", status.ping());
+        gauge!("gns_connection_quality_local", status.quality_local());
+        gauge!("gns_connection_quality_remote", status.quality_remote());
+        gauge!("gns_connection_in_bytes_per_sec", status.in_bytes_per_sec());
+        gauge!(
+            "gns_connection_out_bytes_per_sec",
+            status.out_bytes_per_sec()
+        );
+        gauge!(
+            "gns_connection_in_packets_per_sec",
+            status.in_packets_per_sec()
+        );
+        gauge!(
+            "gns_connection_out_packets_per_sec",
+            status.out_packets_per_sec()
+        );
+        gauge!(
+            "gns_connection_send_rate_bytes_per_sec",
+            status.send_rate_bytes_per_sec()
+        );
+        gauge!(
+            "gns_connection_pending_reliable_bytes",
+            status.pending_bytes_reliable()
+        );
+        gauge!(
+            "gns_connection_pending_unreliable_bytes",
+            status.pending_bytes_unreliable()
+        );
+        gauge!(
+            "gns_connection_sent_unacked_reliable_bytes",
+            status.bytes_sent_unacked_reliable()
+        );
+        gauge!(
+            "gns_connection_queue_time_seconds",
+            status.approximated_queue_time().as_secs_f64()
+        );
+        if let Some(jitter) = status.max_jitter_usec() {
+            gauge!("gns_connection_max_jitter_ms", jitter as f64 / 1000.0);
+        }
+        for (lane, status) in lanes.iter().enumerate() {
+            let labels = [("connection", id.clone()), ("lane", lane.to_string())];
+            ::metrics::gauge!("gns_lane_pending_reliable_bytes", &labels)
+                .set(status.pending_bytes_reliable() as f64);
+            ::metrics::gauge!("gns_lane_pending_unreliable_bytes", &labels)
+                .set(status.pending_bytes_unreliable() as f64);
+            ::metrics::gauge!("gns_lane_sent_unacked_reliable_bytes", &labels)
+                .set(status.bytes_sent_unacked_reliable() as f64);
+            ::metrics::gauge!("gns_lane_queue_time_seconds", &labels)
+                .set(status.approximated_queue_time().as_secs_f64());
+        }
+        true
+    }
+
+    /// Zero the gauges of `connection`, e.g. once it is closed. The
+    /// `metrics` facade cannot remove a series, so the exporter keeps
+    /// reporting them, at zero, until it expires them.
+    pub fn forget(&self, connection: GnsConnection) {
+        let id = connection.into_raw().to_string();
+        for name in CONNECTION_GAUGES {
+            ::metrics::gauge!(name, "connection" => id.clone()).set(0.0);
+        }
+        for lane in 0..self.nb_of_lanes {
+            let labels = [("connection", id.clone()), ("lane", lane.to_string())];
+            for name in LANE_GAUGES {
+                ::metrics::gauge!(name, &labels).set(0.0);
+            }
+        }
+    }
+}
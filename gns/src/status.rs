@@ -0,0 +1,219 @@
+//! Connection names and the detailed status report of GNS.
+//!
+//! The report is free-form text meant for humans; [`DetailedStatus`] pulls
+//! the values worth logging out of it, and completes them with the
+//! real-time status of the connection.
+
+use crate::sys::*;
+use crate::{
+    get_interface, GnsConnection, GnsConnectionRealTimeLaneStatus, GnsError, GnsResult, GnsSocket,
+    IsReady,
+};
+use std::ffi::{c_char, CStr, CString};
+
+/// Length of the name buffer of a GNS connection, NUL included.
+const MAX_NAME_LEN: usize = 128;
+
+/// Values of a detailed status report, see
+/// [`GnsSocket::detailed_status_parsed`]. A value the report does not
+/// contain is `None`; when a value appears in several sections, e.g. current
+/// rates and lifetime stats, the first one is kept.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DetailedStatus {
+    /// Round-trip time in milliseconds.
+    pub ping_ms: Option<u32>,
+    /// Percentage of packets dropped.
+    pub packet_loss_pct: Option<f32>,
+    /// Highest jitter in milliseconds.
+    pub max_jitter_ms: Option<f32>,
+    /// Estimated rate we may send at, in bytes per second.
+    pub send_rate: Option<u32>,
+    /// Bytes sent per second.
+    pub out_bytes_per_sec: Option<f32>,
+    /// Bytes received per second.
+    pub in_bytes_per_sec: Option<f32>,
+    /// Queues of the lanes of the connection.
+    pub lanes: Vec<GnsConnectionRealTimeLaneStatus>,
+    /// Every `label: value` line of the report, in order.
+    pub fields: Vec<(String, String)>,
+}
+
+/// Leading number of `text`, skipping whitespace.
+fn leading_number(text: &str) -> Option<f32> {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+/// Number following `label` in `line`, e.g. the one of `Dropped:` in
+/// `Quality: 99.0% (Dropped:1.00% WeirdSeq:0.00%)`.
+fn number_after(line: &str, label: &str) -> Option<f32> {
+    line.find(label)
+        .and_then(|at| leading_number(&line[at + label.len()..]))
+}
+
+/// Bytes per second of a `12.5 K/sec` rate.
+fn bytes_per_sec(text: &str) -> Option<f32> {
+    let rate = text.split("pkts/sec").last()?;
+    let value = leading_number(rate)?;
+    let unit = rate
+        .trim_start()
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+    Some(match unit.trim_start().chars().next() {
+        Some('K') => value * 1024.0,
+        Some('M') => value * 1024.0 * 1024.0,
+        _ => value,
+    })
+}
+
+impl DetailedStatus {
+    /// Parse the text of [`GnsSocket::detailed_status`]. Lane queues are not
+    /// part of it and are left empty.
+    pub fn parse(report: &str) -> Self {
+        let mut status = DetailedStatus::default();
+        for line in report.lines() {
+            let Some((label, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (label, value) = (label.trim(), value.trim());
+            if value.is_empty() {
+                // Section header.
+                continue;
+            }
+            match label.to_ascii_lowercase().as_str() {
+                "ping" => {
+                    status.ping_ms = status.ping_ms.or(leading_number(value).map(|x| x as u32))
+                }
+                "max jitter" => {
+                    status.max_jitter_ms = status.max_jitter_ms.or(leading_number(value))
+                }
+                "send rate" => {
+                    status.send_rate = status.send_rate.or(leading_number(value).map(|x| x as u32))
+                }
+                "sent" => {
+                    status.out_bytes_per_sec = status.out_bytes_per_sec.or(bytes_per_sec(value))
+                }
+                "recv" => {
+                    status.in_bytes_per_sec = status.in_bytes_per_sec.or(bytes_per_sec(value))
+                }
+                _ => {}
+            }
+            status.packet_loss_pct = status
+                .packet_loss_pct
+                .or_else(|| number_after(line, "Dropped:"));
+            status.fields.push((label.to_owned(), value.to_owned()));
+        }
+        status
+    }
+}
+
+impl<S> GnsSocket<S>
+where
+    S: IsReady,
+{
+    /// Name `connection`, e.g. after the player it belongs to. GNS puts it in
+    /// the description of the connection, which prefixes the debug output
+    /// lines about it. Names longer than 127 bytes are truncated.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Config`] if `name` contains a NUL.
+    pub fn set_connection_name(
+        &self,
+        GnsConnection(conn): GnsConnection,
+        name: &str,
+    ) -> GnsResult<()> {
+        let name = CString::new(name).map_err(|_| GnsError::Config("interior NUL"))?;
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_SetConnectionName(get_interface(), conn, name.as_ptr())
+        };
+        Ok(())
+    }
+
+    /// Name of `connection`, empty if none was set, `None` if the connection
+    /// doesn't exist.
+    pub fn connection_name(&self, GnsConnection(conn): GnsConnection) -> Option<String> {
+        let mut name = [0 as c_char; MAX_NAME_LEN];
+        unsafe {
+            SteamAPI_ISteamNetworkingSockets_GetConnectionName(
+                get_interface(),
+                conn,
+                name.as_mut_ptr(),
+                name.len() as _,
+            )
+        }
+        .then(|| {
+            unsafe { CStr::from_ptr(name.as_ptr()) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    /// Human-readable report on `connection`: addresses, rates, ping, packet
+    /// loss, jitter and more, see [`DetailedStatus`] for its values.
+    ///
+    /// # Errors
+    /// Returns [`GnsError::Api`] with `k_EResultNoConnection` if
+    /// `connection` doesn't exist.
+    pub fn detailed_status(&self, GnsConnection(conn): GnsConnection) -> GnsResult<String> {
+        let mut buf = vec![0u8; 4096];
+        loop {
+            let result = unsafe {
+                SteamAPI_ISteamNetworkingSockets_GetDetailedConnectionStatus(
+                    get_interface(),
+                    conn,
+                    buf.as_mut_ptr() as *mut c_char,
+                    buf.len() as _,
+                )
+            };
+            match result {
+                0 => break,
+                // The buffer was too small, `result` is the size needed.
+                needed if needed > 0 => buf.resize((needed as usize).max(buf.len() * 2), 0),
+                _ => return Err(GnsError::Api(EResult::k_EResultNoConnection)),
+            }
+        }
+        let report = CStr::from_bytes_until_nul(&buf)
+            .map_err(|_| GnsError::Api(EResult::k_EResultNoConnection))?;
+        Ok(report.to_string_lossy().into_owned())
+    }
+
+    /// [`GnsSocket::detailed_status`] parsed, with the queues of the first
+    /// `nb_of_lanes` lanes. Values missing from the report are taken from the
+    /// real-time status, see [`GnsSocket::get_connection_real_time_status`].
+    /// Like it, this resets the jitter high water mark of GNS for
+    /// `connection`.
+    ///
+    /// # Errors
+    /// Same as [`GnsSocket::detailed_status`] and
+    /// [`GnsSocket::get_connection_real_time_status`].
+    pub fn detailed_status_parsed(
+        &self,
+        connection: GnsConnection,
+        nb_of_lanes: u32,
+    ) -> GnsResult<DetailedStatus> {
+        let mut status = DetailedStatus::parse(&self.detailed_status(connection)?);
+        let (real_time, lanes) = self.get_connection_real_time_status(connection, nb_of_lanes)?;
+        status.ping_ms = status.ping_ms.or(Some(real_time.ping()));
+        // The quality is negative while unknown.
+        let quality = real_time.quality_local();
+        status.packet_loss_pct = status
+            .packet_loss_pct
+            .or((quality >= 0.0).then_some((1.0 - quality) * 100.0));
+        status.max_jitter_ms = status
+            .max_jitter_ms
+            .or(real_time.max_jitter_usec().map(|usec| usec as f32 / 1000.0));
+        status.send_rate = status
+            .send_rate
+            .or(Some(real_time.send_rate_bytes_per_sec()));
+        status.out_bytes_per_sec = status
+            .out_bytes_per_sec
+            .or(Some(real_time.out_bytes_per_sec()));
+        status.in_bytes_per_sec = status
+            .in_bytes_per_sec
+            .or(Some(real_time.in_bytes_per_sec()));
+        status.lanes = lanes;
+        Ok(status)
+    }
+}
@@ -0,0 +1,174 @@
+//! `futures` [`Stream`]/[`Sink`] adapters over a [`GnsSocket`] (`futures` feature).
+//!
+//! Wakeups come from the background driver of the `tokio` feature, the
+//! adapters must therefore be polled from within a tokio runtime.
+
+use crate::{
+    EResult, GnsConnectionEvent, GnsMessageNumber, GnsNetworkMessage, GnsSocket, IsReady,
+    SendOutcome, ToReceive, ToSend,
+};
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// [`Stream`] of the messages received on a socket, see
+/// [`GnsSocket::message_stream`].
+///
+/// The stream ends if the underlying connection or poll group handle
+/// becomes invalid.
+pub struct MessageStream<'a, S> {
+    socket: &'a GnsSocket<S>,
+    done: bool,
+}
+
+impl<S> Stream for MessageStream<'_, S>
+where
+    S: IsReady,
+{
+    type Item = GnsNetworkMessage<ToReceive>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.socket.poll_recv_message(cx) {
+            Poll::Ready(Ok(message)) => Poll::Ready(Some(message)),
+            Poll::Ready(Err(_)) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Never-ending [`Stream`] of the connection events of a socket, see
+/// [`GnsSocket::event_stream`].
+pub struct EventStream<'a, S> {
+    socket: &'a GnsSocket<S>,
+}
+
+impl<S> Stream for EventStream<'_, S>
+where
+    S: IsReady,
+{
+    type Item = GnsConnectionEvent;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.socket.poll_next_event(cx).map(Some)
+    }
+}
+
+/// A message GNS refused to queue, handed back to the caller by
+/// [`MessageSink`]. Mirrors [`SendOutcome::Failed`].
+#[derive(thiserror::Error)]
+#[error("send failed: {result:?}")]
+pub struct SendError {
+    pub result: EResult,
+    pub message: GnsNetworkMessage<ToSend>,
+}
+
+impl core::fmt::Debug for SendError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SendError")
+            .field("result", &self.result)
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`Sink`] dispatching each message to its target connection, see
+/// [`GnsSocket::message_sink`].
+///
+/// Messages are handed to GNS as soon as they are sent to the sink (GNS does
+/// its own queuing and Nagle batching), so flushing and closing are no-ops.
+pub struct MessageSink<'a, S> {
+    socket: &'a GnsSocket<S>,
+    last_sent: Option<GnsMessageNumber>,
+}
+
+impl<S> MessageSink<'_, S> {
+    /// Number of the last message successfully queued through this sink.
+    #[inline]
+    pub fn last_sent(&self) -> Option<GnsMessageNumber> {
+        self.last_sent
+    }
+}
+
+impl<S> Sink<GnsNetworkMessage<ToSend>> for MessageSink<'_, S>
+where
+    S: IsReady,
+{
+    type Error = SendError;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        message: GnsNetworkMessage<ToSend>,
+    ) -> Result<(), Self::Error> {
+        match self.socket.send_messages(core::iter::once(message)).pop() {
+            Some(SendOutcome::Sent(number)) => {
+                self.last_sent = Some(number);
+                Ok(())
+            }
+            Some(SendOutcome::Failed(result, message)) => Err(SendError { result, message }),
+            // A one-message batch has no earlier failure to be skipped
+            // behind; handled anyway rather than panicking.
+            Some(SendOutcome::Skipped(message)) => Err(SendError {
+                result: EResult::k_EResultFail,
+                message,
+            }),
+            None => unreachable!("send_messages yields one outcome per message; qed;"),
+        }
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> GnsSocket<S>
+where
+    S: IsReady,
+{
+    /// Borrow the socket as a [`Stream`] of received messages.
+    ///
+    /// Like [`recv_message`](Self::recv_message), dropping the stream never
+    /// loses a message.
+    #[inline]
+    pub fn message_stream(&self) -> MessageStream<'_, S> {
+        MessageStream {
+            socket: self,
+            done: false,
+        }
+    }
+
+    /// Borrow the socket as a [`Stream`] of connection events.
+    #[inline]
+    pub fn event_stream(&self) -> EventStream<'_, S> {
+        EventStream { socket: self }
+    }
+
+    /// Borrow the socket as a [`Sink`] of outbound messages. Failed sends
+    /// are surfaced as [`SendError`], which hands the message back.
+    #[inline]
+    pub fn message_sink(&self) -> MessageSink<'_, S> {
+        MessageSink {
+            socket: self,
+            last_sent: None,
+        }
+    }
+}
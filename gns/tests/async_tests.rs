@@ -0,0 +1,137 @@
+//! Tests for the async receive API (`tokio` feature): `recv_message` and
+//! `next_event` driven by the background callback driver, without any
+//! hand-written `poll_callbacks` loop.
+#![cfg(feature = "tokio")]
+
+use gns::{ConnectionTransition, GnsGlobal, GnsSocket, IsClient, IsServer, SendFlags};
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+mod common;
+use common::free_port;
+
+/// Establish a connected server/client pair using only `next_event`.
+async fn connected_pair() -> (&'static GnsGlobal, GnsSocket<IsServer>, GnsSocket<IsClient>) {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+
+    let handshake = async {
+        loop {
+            tokio::select! {
+                event = server.next_event() => {
+                    if event.transition() == ConnectionTransition::IncomingRequest {
+                        server.accept(event.connection()).expect("accept failed");
+                    }
+                }
+                event = client.next_event() => {
+                    if event.transition() == ConnectionTransition::Connected {
+                        break;
+                    }
+                }
+            }
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), handshake)
+        .await
+        .expect("client did not connect within the timeout");
+    (gns_global, server, client)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recv_message_and_next_event() {
+    let (gns_global, server, client) = connected_pair().await;
+
+    let msg = gns_global.utils().allocate_message(
+        client.connection(),
+        SendFlags::RELIABLE,
+        &b"hello async"[..],
+    );
+    client.send_message(msg).expect("send_message failed");
+
+    let message = tokio::time::timeout(Duration::from_secs(10), server.recv_message())
+        .await
+        .expect("no message within the timeout")
+        .expect("recv_message failed");
+    assert_eq!(message.payload(), b"hello async");
+}
+
+/// Racing `recv_message` against a timer that usually wins must neither lose
+/// nor duplicate any message: every cancelled future leaves the message in GNS.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recv_message_is_cancel_safe() {
+    let (gns_global, server, client) = connected_pair().await;
+
+    const N: usize = 50;
+    for i in 0..N {
+        let msg = gns_global.utils().allocate_message(
+            client.connection(),
+            SendFlags::RELIABLE,
+            format!("msg-{i}"),
+        );
+        client.send_message(msg).expect("send_message failed");
+    }
+
+    let mut received = Vec::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    while received.len() < N && tokio::time::Instant::now() < deadline {
+        tokio::select! {
+            message = server.recv_message() => {
+                let message = message.expect("recv_message failed");
+                received.push(String::from_utf8(message.payload().to_vec()).unwrap());
+            }
+            _ = tokio::time::sleep(Duration::from_micros(100)) => {}
+        }
+    }
+
+    assert_eq!(received.len(), N, "some messages were lost");
+    for (i, got) in received.iter().enumerate() {
+        assert_eq!(got, &format!("msg-{i}"), "reliable order not preserved");
+    }
+}
+
+/// The message the driver takes from GNS while a task waits is returned
+/// first, by the async and the sync receive alike.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recv_message_then_receive_messages_keeps_order() {
+    let (gns_global, server, client) = connected_pair().await;
+
+    let send = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for i in 0..3 {
+            let msg = gns_global.utils().allocate_message(
+                client.connection(),
+                SendFlags::RELIABLE,
+                format!("msg-{i}"),
+            );
+            client.send_message(msg).expect("send_message failed");
+        }
+    };
+    let (message, ()) = tokio::join!(
+        tokio::time::timeout(Duration::from_secs(10), server.recv_message()),
+        send
+    );
+    let message = message
+        .expect("no message within the timeout")
+        .expect("recv_message failed");
+    assert_eq!(message.payload(), b"msg-0");
+
+    let mut received = Vec::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while received.len() < 2 && tokio::time::Instant::now() < deadline {
+        for message in server
+            .receive_messages::<8>()
+            .expect("receive_messages failed")
+        {
+            received.push(String::from_utf8(message.payload().to_vec()).unwrap());
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(received, ["msg-1", "msg-2"]);
+}
@@ -0,0 +1,123 @@
+//! Authentication with certificates signed by a trusted CA. Lives in its own
+//! test binary because the local identity must match the certificate.
+//!
+//! `data/gns-test.pem` certifies `str:gns-test` and is signed by the
+//! `data/test-ca` CA of `gns-cert`. GNS only trusts that CA when built with
+//! `GNS_TRUSTED_CA_KEYS` pointing at `data/test-ca.pub`, otherwise the tests
+//! connecting peers have nothing to check and return early.
+
+use gns::{
+    ConnectionInfoFlags, ConnectionTransition, GnsConnectionEvent, GnsGlobal, GnsIdentity,
+    GnsInitOptions, GnsSocket,
+};
+
+use std::time::{Duration, Instant};
+
+const TEST_CA: &str = include_str!("data/test-ca.pub");
+const CERTIFICATE: &[u8] = include_bytes!("data/gns-test.pem");
+
+fn global() -> &'static GnsGlobal {
+    GnsGlobal::get_with(GnsInitOptions {
+        identity: Some(GnsIdentity::from_generic_string("gns-test").unwrap()),
+        ..Default::default()
+    })
+    .expect("Failed to initialize GNS global")
+}
+
+/// Whether the peer of a connection was authenticated by its certificate.
+fn authenticated(event: &GnsConnectionEvent) -> bool {
+    !event
+        .info()
+        .flags()
+        .contains(ConnectionInfoFlags::UNAUTHENTICATED)
+}
+
+/// Whether this build of GNS trusts the test CA.
+fn trusts_test_ca(gns_global: &GnsGlobal) -> bool {
+    let trusted = gns_global
+        .auth()
+        .trusted_ca_keys()
+        .contains(&TEST_CA.trim());
+    if !trusted {
+        eprintln!("GNS_TRUSTED_CA_KEYS does not list tests/data/test-ca.pub, skipping");
+    }
+    trusted
+}
+
+/// A certificate and key issued by `gns-cert` load for the identity they
+/// were issued to.
+#[test]
+fn test_issued_certificate_loads() {
+    let gns_global = global();
+    gns_global
+        .auth()
+        .set_certificate(CERTIFICATE)
+        .expect("set_certificate failed");
+}
+
+#[test]
+fn test_signed_peers_connect_authenticated() {
+    let gns_global = global();
+    if !trusts_test_ca(gns_global) {
+        return;
+    }
+
+    gns_global
+        .auth()
+        .set_certificate(CERTIFICATE)
+        .expect("set_certificate failed");
+    gns_global
+        .auth()
+        .require_authentication(true)
+        .expect("require_authentication failed");
+
+    let server = GnsSocket::new(gns_global)
+        .listen_on("127.0.0.1:0")
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect_to(server.local_addr().unwrap())
+        .expect("Failed to create client socket");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut connected = (false, false);
+    while connected != (true, true) {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for connection"
+        );
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            match event.transition() {
+                ConnectionTransition::IncomingRequest => {
+                    server.accept(event.connection()).expect("accept failed");
+                }
+                ConnectionTransition::Connected => {
+                    assert!(authenticated(&event), "client not authenticated");
+                    connected.0 = true;
+                }
+                ConnectionTransition::ClosedByPeer
+                | ConnectionTransition::ProblemDetectedLocally => {
+                    panic!("server side failed: {}", event.info().end_debug())
+                }
+                _ => {}
+            }
+        }
+        for event in client.receive_events() {
+            match event.transition() {
+                ConnectionTransition::Connected => {
+                    assert!(authenticated(&event), "server not authenticated");
+                    connected.1 = true;
+                }
+                ConnectionTransition::ClosedByPeer
+                | ConnectionTransition::ProblemDetectedLocally => {
+                    panic!("client side failed: {}", event.info().end_debug())
+                }
+                _ => {}
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let status = gns_global.auth().status();
+    assert!(status.is_ready(), "not authenticated: {}", status.debug);
+}
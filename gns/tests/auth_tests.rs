@@ -0,0 +1,133 @@
+//! Tests for `GnsAuth`. Lives in its own test binary because requiring
+//! authentication changes global configuration.
+
+use gns::{
+    config, ConfigScope, ConnectionTransition, GnsConfigValue, GnsError, GnsGlobal, GnsSocket,
+};
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Serializes the tests of this binary, they share the global auth policy.
+static SUITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_status_and_certificate_request() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+
+    // No certificate was loaded, so authentication cannot be current.
+    assert!(!gns_global.auth().status().is_ready());
+
+    let request = gns_global
+        .auth()
+        .certificate_request()
+        .expect("certificate_request failed");
+    assert!(!request.is_empty());
+}
+
+#[test]
+fn test_set_certificate_rejects_garbage() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+
+    match gns_global.auth().set_certificate(b"not a certificate") {
+        Err(GnsError::Auth(msg)) => assert!(!msg.is_empty(), "GNS gave no reason"),
+        other => panic!("expected Err(Auth), got {other:?}"),
+    }
+}
+
+/// With authentication required, peers presenting only the self-signed
+/// certificate GNS makes up never get connected; lifting the requirement
+/// lets them in again.
+#[test]
+fn test_require_authentication_refuses_unsigned_peers() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+
+    let attempt = || -> bool {
+        let server = GnsSocket::new(gns_global)
+            .listen_on("127.0.0.1:0")
+            .expect("Failed to create server socket");
+        let client = GnsSocket::new(gns_global)
+            .connect_to(server.local_addr().unwrap())
+            .expect("Failed to create client socket");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            gns_global.poll_callbacks();
+            for event in server.receive_events() {
+                if event.transition() == ConnectionTransition::IncomingRequest {
+                    let _ = server.accept(event.connection());
+                }
+            }
+            for event in client.receive_events() {
+                match event.transition() {
+                    ConnectionTransition::Connected => return true,
+                    ConnectionTransition::ClosedByPeer
+                    | ConnectionTransition::ProblemDetectedLocally => return false,
+                    _ => {}
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    };
+
+    gns_global
+        .auth()
+        .require_authentication(true)
+        .expect("require_authentication failed");
+    let refused = !attempt();
+    gns_global
+        .auth()
+        .require_authentication(false)
+        .expect("require_authentication failed");
+
+    assert!(refused, "unauthenticated peer was connected");
+    assert!(attempt(), "peer refused after lifting the requirement");
+}
+
+/// Lifting the requirement restores the global values set before it, and
+/// leaves the unset ones to their default.
+#[test]
+fn test_require_authentication_restores_global_values() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+    let default = utils
+        .get_config(ConfigScope::Global, config::IP_LOCALHOST_ALLOW_WITHOUT_AUTH)
+        .unwrap();
+    utils
+        .set_config(ConfigScope::Global, config::IP_ALLOW_WITHOUT_AUTH, 2)
+        .unwrap();
+
+    let auth = gns_global.auth();
+    auth.require_authentication(true)
+        .expect("require_authentication failed");
+    // Requiring it twice must not save the values it set.
+    auth.require_authentication(true)
+        .expect("require_authentication failed");
+    assert_eq!(
+        utils.get_config(ConfigScope::Global, config::IP_ALLOW_WITHOUT_AUTH),
+        Ok(0)
+    );
+    auth.require_authentication(false)
+        .expect("require_authentication failed");
+
+    assert_eq!(
+        utils.get_config(ConfigScope::Global, config::IP_ALLOW_WITHOUT_AUTH),
+        Ok(2)
+    );
+    let (localhost, inherited) = utils
+        .get_config_value(
+            ConfigScope::Global,
+            config::IP_LOCALHOST_ALLOW_WITHOUT_AUTH.value(),
+        )
+        .unwrap();
+    assert!(inherited, "default not restored");
+    assert_eq!(localhost, GnsConfigValue::Int32(default));
+
+    utils
+        .reset_config(ConfigScope::Global, config::IP_ALLOW_WITHOUT_AUTH)
+        .unwrap();
+}
@@ -0,0 +1,119 @@
+//! Tests for sending within a budget, see `GnsSocket::send_with_budget`.
+
+use gns::{
+    config, BudgetedSend, GnsGlobal, GnsLane, GnsSocket, IsClient, SendBudget, SendBudgets,
+    SendFlags,
+};
+
+use std::time::Duration;
+
+/// Slow down the sends of `socket` to 1KB/s, then queue 64KB of reliable
+/// messages on it.
+fn back_up(gns_global: &'static GnsGlobal, socket: &GnsSocket<IsClient>) {
+    let connection = socket.connection();
+    for key in [config::SEND_RATE_MIN, config::SEND_RATE_MAX] {
+        gns_global
+            .utils()
+            .set_config(connection, key, 1024)
+            .expect("set_config failed");
+    }
+    for _ in 0..16 {
+        let msg =
+            gns_global
+                .utils()
+                .allocate_message(connection, SendFlags::RELIABLE, vec![0u8; 4096]);
+        socket.send_message(msg).expect("send_message failed");
+    }
+}
+
+/// Within budget, a message is sent as usual.
+#[test]
+fn test_send_within_budget() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(true)
+        .expect("Failed to create socket pair");
+
+    let msg = gns_global.utils().allocate_message(
+        left.connection(),
+        SendFlags::RELIABLE,
+        vec![0u8; 1 << 20],
+    );
+    // Larger than the budget, but the queue is empty.
+    let budget = SendBudget::new(Duration::from_secs(1), 1024);
+    assert!(matches!(
+        left.try_send(msg, budget).expect("try_send failed"),
+        BudgetedSend::Sent(_)
+    ));
+    assert_eq!(
+        left.backpressure(left.connection(), 0, 0, &SendBudgets::new())
+            .expect("backpressure failed"),
+        None
+    );
+}
+
+/// Over budget, reliable messages are deferred and unreliable ones dropped.
+#[test]
+fn test_send_over_connection_budget() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(true)
+        .expect("Failed to create socket pair");
+    back_up(gns_global, &left);
+    let budget = SendBudget::new(Duration::from_secs(3600), 16 * 1024);
+
+    let reliable =
+        gns_global
+            .utils()
+            .allocate_message(left.connection(), SendFlags::RELIABLE, &b"state"[..]);
+    match left.try_send(reliable, budget).expect("try_send failed") {
+        BudgetedSend::Deferred(message, backpressure) => {
+            assert_eq!(message.payload(), b"state");
+            assert_eq!(backpressure.lane, None);
+            assert!(backpressure.pending_bytes > 16 * 1024);
+        }
+        _ => panic!("the reliable message was not deferred"),
+    }
+
+    let unreliable = gns_global.utils().allocate_message(
+        left.connection(),
+        SendFlags::UNRELIABLE,
+        &b"state"[..],
+    );
+    assert!(matches!(
+        left.try_send(unreliable, budget).expect("try_send failed"),
+        BudgetedSend::Dropped(_)
+    ));
+}
+
+/// A lane budget only applies to the messages of its lane.
+#[test]
+fn test_send_over_lane_budget() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(true)
+        .expect("Failed to create socket pair");
+    left.configure_connection_lanes(left.connection(), &[GnsLane::new(0, 1), GnsLane::new(0, 1)])
+        .expect("configure_connection_lanes failed");
+    back_up(gns_global, &left);
+    let budgets = SendBudgets::new().lane(0, SendBudget::new(Duration::from_secs(3600), 1024));
+
+    let on_lane = |lane| {
+        gns_global
+            .utils()
+            .allocate_message(left.connection(), SendFlags::UNRELIABLE, &b"state"[..])
+            .set_lane(lane)
+    };
+    match left
+        .send_with_budget(on_lane(0), &budgets)
+        .expect("send_with_budget failed")
+    {
+        BudgetedSend::Dropped(backpressure) => assert_eq!(backpressure.lane, Some(0)),
+        _ => panic!("the message of the backed up lane was not dropped"),
+    }
+    assert!(matches!(
+        left.send_with_budget(on_lane(1), &budgets)
+            .expect("send_with_budget failed"),
+        BudgetedSend::Sent(_)
+    ));
+}
@@ -2,7 +2,10 @@
 //! These tests verify the configuration and debug capabilities of the GNS library
 
 use gns::sys::*;
-use gns::{GnsConfig, GnsGlobal, GnsSocket, SendFlags};
+use gns::{
+    config, ConfigScope, GnsConfig, GnsConfigValue, GnsGlobal, GnsSocket, GnsSocketBuilder,
+    SendFlags,
+};
 
 use std::net::Ipv4Addr;
 
@@ -100,3 +103,139 @@ fn test_connection_info() {
     // It should return None for an invalid connection
     assert!(info.is_none(), "Expected None for invalid connection info");
 }
+
+#[test]
+fn test_typed_config_scopes() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), free_port())
+        .expect("Failed to create server socket");
+    let listener = server.listeners()[0];
+
+    // Set on the listen socket only, the global value is untouched.
+    let global_rate = utils
+        .get_config(ConfigScope::Global, config::SEND_RATE_MAX)
+        .expect("get_config failed");
+    utils
+        .set_config(listener, config::SEND_RATE_MAX, 123_456)
+        .expect("set_config failed");
+    assert_eq!(
+        utils.get_config(listener, config::SEND_RATE_MAX),
+        Ok(123_456)
+    );
+    assert_eq!(
+        utils.get_config(ConfigScope::Global, config::SEND_RATE_MAX),
+        Ok(global_rate)
+    );
+
+    // Resetting inherits the global value again.
+    utils
+        .reset_config(listener, config::SEND_RATE_MAX)
+        .expect("reset_config failed");
+    let (value, inherited) = utils
+        .get_config_value(listener, config::SEND_RATE_MAX.value())
+        .expect("get_config_value failed");
+    assert_eq!(value, GnsConfigValue::Int32(global_rate));
+    assert!(inherited);
+
+    utils
+        .set_config(
+            ConfigScope::Global,
+            config::P2P_TURN_SERVER_LIST,
+            "turn.example.com:3478",
+        )
+        .expect("set_config failed");
+    assert_eq!(
+        utils.get_config(ConfigScope::Global, config::P2P_TURN_SERVER_LIST),
+        Ok("turn.example.com:3478".to_string())
+    );
+
+    // A connection that doesn't exist is not a valid scope.
+    assert!(utils
+        .get_config(gns::GnsConnection::default(), config::TIMEOUT_CONNECTED)
+        .is_err());
+}
+
+#[test]
+fn test_config_values_enumeration() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+
+    let info = utils
+        .config_values(false)
+        .find(|info| info.value == config::TIMEOUT_INITIAL.value())
+        .expect("TimeoutInitial not listed");
+    assert_eq!(info.name, "TimeoutInitial");
+    assert_eq!(
+        info.data_type,
+        ESteamNetworkingConfigDataType::k_ESteamNetworkingConfig_Int32
+    );
+    assert_eq!(
+        info.scope,
+        ESteamNetworkingConfigScope::k_ESteamNetworkingConfig_Connection
+    );
+
+    // Every listed value can be read globally.
+    for info in utils.config_values(false) {
+        assert!(
+            utils
+                .get_config_value(ConfigScope::Global, info.value)
+                .is_ok(),
+            "{} not readable",
+            info.name
+        );
+    }
+
+    // The enumeration does not start over once exhausted.
+    let mut values = utils.config_values(false);
+    let count = values.by_ref().count();
+    assert!(count > 0);
+    assert!(values.next().is_none());
+}
+
+#[test]
+fn test_builder_options_applied_at_creation() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+    let port = free_port();
+
+    let server = GnsSocketBuilder::new(gns_global)
+        .option(config::TIMEOUT_CONNECTED, 4_321)
+        .option(config::SEND_RATE_MAX, 100_000)
+        .option(config::SEND_RATE_MAX, 200_000)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let listener = server.listeners()[0];
+    assert_eq!(
+        utils.get_config(listener, config::TIMEOUT_CONNECTED),
+        Ok(4_321)
+    );
+    // The last value given wins.
+    assert_eq!(
+        utils.get_config(listener, config::SEND_RATE_MAX),
+        Ok(200_000)
+    );
+
+    let second = server
+        .add_listener(Ipv4Addr::LOCALHOST.into(), free_port())
+        .expect("add_listener failed");
+    assert_eq!(
+        utils.get_config(second, config::TIMEOUT_CONNECTED),
+        Ok(4_321)
+    );
+
+    let client = GnsSocketBuilder::new(gns_global)
+        .option(config::TIMEOUT_INITIAL, 1_234)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+    assert_eq!(
+        utils.get_config(client.connection(), config::TIMEOUT_INITIAL),
+        Ok(1_234)
+    );
+
+    assert!(GnsSocketBuilder::new(gns_global)
+        .option(config::P2P_STUN_SERVER_LIST, "bad\0string")
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .is_err());
+}
@@ -0,0 +1,97 @@
+//! Tests for the typed [`ConnectionState`] / [`ConnectionTransition`] view
+//! over GNS connection-status callbacks.
+
+use gns::sys::*;
+use gns::{ConnectionState, ConnectionTransition, EndReason, GnsGlobal, GnsSocket};
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+/// Every raw state GNS can report maps to a typed state and back unchanged.
+#[test]
+fn test_connection_state_round_trips_raw_value() {
+    use ESteamNetworkingConnectionState::*;
+    for raw in [
+        k_ESteamNetworkingConnectionState_None,
+        k_ESteamNetworkingConnectionState_Connecting,
+        k_ESteamNetworkingConnectionState_FindingRoute,
+        k_ESteamNetworkingConnectionState_Connected,
+        k_ESteamNetworkingConnectionState_ClosedByPeer,
+        k_ESteamNetworkingConnectionState_ProblemDetectedLocally,
+        k_ESteamNetworkingConnectionState_FinWait,
+        k_ESteamNetworkingConnectionState_Linger,
+        k_ESteamNetworkingConnectionState_Dead,
+    ] {
+        let typed = ConnectionState::from(raw);
+        assert_eq!(ESteamNetworkingConnectionState::from(typed), raw);
+    }
+    assert!(ConnectionState::ClosedByPeer.is_closed());
+    assert!(ConnectionState::ProblemDetectedLocally.is_closed());
+    assert!(!ConnectionState::Connected.is_closed());
+}
+
+/// A full connect / close cycle is reported as `IncomingRequest` then
+/// `Connected` on the server, `Connecting` then `Connected` on the client,
+/// and `ClosedByPeer` on the server once the client goes away.
+#[test]
+fn test_connection_transitions_over_lifecycle() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+
+    let mut server_transitions = Vec::new();
+    let mut client_transitions = Vec::new();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !client_transitions.contains(&ConnectionTransition::Connected)
+        && Instant::now() < deadline
+    {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            let transition = event.transition();
+            if transition == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+            }
+            server_transitions.push(transition);
+        }
+        client_transitions.extend(client.receive_events().map(|event| event.transition()));
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    assert_eq!(
+        client_transitions.first(),
+        Some(&ConnectionTransition::Connecting)
+    );
+    assert!(client_transitions.contains(&ConnectionTransition::Connected));
+    assert_eq!(
+        server_transitions.first(),
+        Some(&ConnectionTransition::IncomingRequest)
+    );
+
+    drop(client);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut closed = None;
+    while closed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::ClosedByPeer {
+                assert_eq!(event.info().state(), ConnectionState::ClosedByPeer);
+                closed = Some(event.connection());
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let closed = closed.expect("server never saw the client close");
+    server
+        .close_connection(closed, EndReason::App(0), None, false)
+        .expect("close_connection failed");
+}
@@ -0,0 +1,58 @@
+//! Tests for the connections a socket tracks, see `GnsSocket::connections`.
+
+use gns::{ConnectionTransition, EndReason, GnsGlobal, GnsSocket};
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+/// A server tracks the connections it accepted until it closes them, a
+/// client its own connection.
+#[test]
+fn test_server_tracks_connections() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let clients = [0; 2].map(|_| {
+        GnsSocket::new(gns_global)
+            .connect(Ipv4Addr::LOCALHOST.into(), port)
+            .expect("Failed to create client socket")
+    });
+    for client in &clients {
+        assert_eq!(client.connections(), vec![client.connection()]);
+    }
+
+    let mut connected = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while connected.len() < clients.len() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            match event.transition() {
+                ConnectionTransition::IncomingRequest => {
+                    server.accept(event.connection()).expect("accept failed")
+                }
+                ConnectionTransition::Connected => connected.push(event.connection()),
+                _ => {}
+            }
+        }
+        for client in &clients {
+            for _event in client.receive_events() {}
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(connected.len(), clients.len(), "clients did not connect");
+
+    let mut tracked = server.connections();
+    tracked.sort();
+    connected.sort();
+    assert_eq!(tracked, connected);
+
+    server
+        .close_connection(connected[0], EndReason::App(0), None, false)
+        .expect("close_connection failed");
+    assert_eq!(server.connections(), vec![connected[1]]);
+}
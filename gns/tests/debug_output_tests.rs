@@ -2,7 +2,7 @@
 //! a bare `fn` pointer) and actually invokes it from GNS's service thread.
 
 use gns::sys::*;
-use gns::{GnsGlobal, GnsSocket};
+use gns::{ConnectionTransition, GnsGlobal, GnsSocket};
 
 use std::net::Ipv4Addr;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -43,11 +43,7 @@ fn test_debug_output_capturing_closure() {
     while calls.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
         gns_global.poll_callbacks();
         for event in server.receive_events() {
-            if let (
-                ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-            ) = (event.old_state(), event.info().state())
-            {
+            if event.transition() == ConnectionTransition::IncomingRequest {
                 let _ = server.accept(event.connection());
             }
         }
@@ -0,0 +1,104 @@
+//! The debug callback is process-wide: these tests replace and remove it, so
+//! they live in their own binary, apart from `debug_output_tests`.
+
+use gns::sys::*;
+use gns::{GnsGlobal, GnsSocket};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Open and close a connection, which GNS reports on its debug output.
+fn generate_output(gns_global: &'static GnsGlobal) {
+    let (left, right) = GnsSocket::new(gns_global)
+        .socket_pair(true)
+        .expect("Failed to create socket pair");
+    left.set_connection_name(left.connection(), "debug-sink")
+        .expect("set_connection_name failed");
+    drop((left, right));
+    for _ in 0..10 {
+        gns_global.poll_callbacks();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Generate output until `counter` moves.
+fn wait_for(gns_global: &'static GnsGlobal, counter: &AtomicUsize) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while counter.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+        generate_output(gns_global);
+    }
+    assert!(
+        counter.load(Ordering::SeqCst) > 0,
+        "the debug callback was never invoked"
+    );
+}
+
+/// Counter incremented by each line of output, and the callback doing it.
+fn counting() -> (
+    Arc<AtomicUsize>,
+    impl Fn(ESteamNetworkingSocketsDebugOutputType, &str) + Send + Sync + 'static,
+) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    (calls, move |_ty, _msg: &str| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    })
+}
+
+/// Number of calls once the callbacks in flight are done.
+fn settled(calls: &AtomicUsize) -> usize {
+    std::thread::sleep(Duration::from_millis(50));
+    calls.load(Ordering::SeqCst)
+}
+
+#[test]
+fn test_debug_output_replace_level_and_disable() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let everything =
+        ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_Everything;
+
+    let (first, callback) = counting();
+    gns_global.utils().enable_debug_output(everything, callback);
+    wait_for(gns_global, &first);
+
+    // A new callback replaces the first one.
+    let (second, callback) = counting();
+    gns_global.utils().enable_debug_output(everything, callback);
+    let first_calls = settled(&first);
+    wait_for(gns_global, &second);
+    assert_eq!(
+        first.load(Ordering::SeqCst),
+        first_calls,
+        "the replaced callback was still invoked"
+    );
+
+    // Opening and closing a connection raises no bug.
+    gns_global.utils().set_debug_output_level(
+        ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_Bug,
+    );
+    let second_calls = settled(&second);
+    generate_output(gns_global);
+    assert_eq!(
+        settled(&second),
+        second_calls,
+        "output below the level was forwarded"
+    );
+
+    gns_global.utils().set_debug_output_level(everything);
+    let second_calls = settled(&second);
+    generate_output(gns_global);
+    assert!(
+        settled(&second) > second_calls,
+        "restoring the level did not restore the output"
+    );
+
+    gns_global.utils().disable_debug_output();
+    let second_calls = settled(&second);
+    generate_output(gns_global);
+    assert_eq!(
+        settled(&second),
+        second_calls,
+        "the callback was invoked once removed"
+    );
+}
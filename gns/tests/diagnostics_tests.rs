@@ -0,0 +1,189 @@
+//! Tests for per-subsystem log levels and packet tracing, see
+//! `GnsDiagnostics`.
+
+use gns::{
+    ConfigScope, ConnectionTransition, GnsConnection, GnsGlobal, GnsSocket, IsClient, IsServer,
+    LogLevel, LogSubsystem, SendFlags,
+};
+
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+/// A connection inherits the global level until it has its own.
+#[test]
+fn test_log_levels_by_scope() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let diagnostics = gns_global.diagnostics();
+    let (left, right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+
+    diagnostics
+        .set_log_levels(ConfigScope::Global, LogLevel::Warning)
+        .expect("set_log_levels failed");
+    diagnostics
+        .set_log_level(
+            left.connection(),
+            LogSubsystem::PacketGaps,
+            LogLevel::Verbose,
+        )
+        .expect("set_log_level failed");
+
+    for subsystem in LogSubsystem::ALL {
+        assert_eq!(
+            diagnostics
+                .log_level(ConfigScope::Global, subsystem)
+                .unwrap(),
+            LogLevel::Warning
+        );
+        assert_eq!(
+            diagnostics
+                .log_level(right.connection(), subsystem)
+                .unwrap(),
+            LogLevel::Warning
+        );
+    }
+    assert_eq!(
+        diagnostics
+            .log_level(left.connection(), LogSubsystem::PacketGaps)
+            .unwrap(),
+        LogLevel::Verbose
+    );
+    assert_eq!(
+        diagnostics
+            .log_level(left.connection(), LogSubsystem::Message)
+            .unwrap(),
+        LogLevel::Warning
+    );
+
+    diagnostics
+        .set_log_levels(ConfigScope::Global, LogLevel::Important)
+        .expect("set_log_levels failed");
+}
+
+fn connect(gns_global: &'static GnsGlobal, port: u16) -> GnsSocket<IsClient> {
+    GnsSocket::new(gns_global)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket")
+}
+
+/// Accept `count` connections on `server`.
+fn accept(
+    gns_global: &'static GnsGlobal,
+    server: &GnsSocket<IsServer>,
+    clients: &[&GnsSocket<IsClient>],
+    count: usize,
+) -> Vec<GnsConnection> {
+    let mut connected = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while connected.len() < count && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            match event.transition() {
+                ConnectionTransition::IncomingRequest => {
+                    server.accept(event.connection()).expect("accept failed")
+                }
+                ConnectionTransition::Connected => connected.push(event.connection()),
+                _ => {}
+            }
+        }
+        for client in clients {
+            for _event in client.receive_events() {}
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(connected.len(), count, "clients did not connect in time");
+    connected
+}
+
+/// Only the packets of the traced connection reach the trace sink, and
+/// none reach the debug callback.
+#[test]
+fn test_packet_trace_single_connection() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let debug_lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    {
+        let debug_lines = Arc::clone(&debug_lines);
+        gns_global
+            .utils()
+            .enable_debug_output(LogLevel::Warning.into(), move |_ty, msg| {
+                debug_lines.lock().unwrap().push(msg.to_owned())
+            });
+    }
+
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let first_client = connect(gns_global, port);
+    let second_client = connect(gns_global, port);
+    accept(gns_global, &server, &[&first_client, &second_client], 2);
+    // The other connections, second client included, must not be traced.
+    let traced = first_client.connection();
+
+    let traced_lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    {
+        let traced_lines = Arc::clone(&traced_lines);
+        gns_global
+            .diagnostics()
+            .trace_packets(traced, 16, move |line| {
+                traced_lines.lock().unwrap().push(line.to_owned())
+            })
+            .expect("trace_packets failed");
+    }
+
+    let send = |client: &GnsSocket<IsClient>| {
+        let msg = gns_global.utils().allocate_message(
+            client.connection(),
+            SendFlags::RELIABLE,
+            &b"traced"[..],
+        );
+        client.send_message(msg).expect("send_message failed");
+    };
+    for _ in 0..20 {
+        send(&second_client);
+        gns_global.poll_callbacks();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(
+        traced_lines.lock().unwrap().is_empty(),
+        "a packet of another connection was traced"
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while traced_lines.lock().unwrap().is_empty() && Instant::now() < deadline {
+        send(&first_client);
+        gns_global.poll_callbacks();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    gns_global
+        .diagnostics()
+        .stop_packet_trace()
+        .expect("stop_packet_trace failed");
+
+    let traced_count = traced_lines.lock().unwrap().len();
+    assert!(traced_count > 0, "no packet was traced");
+    assert!(
+        debug_lines
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|line| !line.starts_with("[Trace ")),
+        "a packet trace reached the debug callback"
+    );
+
+    send(&first_client);
+    send(&second_client);
+    gns_global.poll_callbacks();
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(
+        traced_lines.lock().unwrap().len(),
+        traced_count,
+        "packets were traced once stopped"
+    );
+    gns_global.utils().disable_debug_output();
+}
@@ -0,0 +1,145 @@
+//! Tests for the typed [`EndReason`] used by `close_connection` and
+//! `GnsConnectionInfo::end_reason`.
+
+use gns::sys::*;
+use gns::{
+    ConnectionTransition, EndReason, GnsError, GnsGlobal, GnsSocket, LocalEndReason, MiscEndReason,
+    RemoteEndReason,
+};
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+/// Every raw value, documented or not, classifies into a typed reason that
+/// converts back to the very same raw value.
+#[test]
+fn test_end_reason_round_trips_raw_value() {
+    for raw in (0..7000).step_by(7).chain([
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Remote_Timeout as u32,
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Misc_Timeout as u32,
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Local_Rights as u32,
+    ]) {
+        assert_eq!(EndReason::from_raw(raw).raw(), raw, "raw value {raw}");
+    }
+}
+
+#[test]
+fn test_end_reason_classifies_ranges() {
+    assert_eq!(EndReason::from_raw(0), EndReason::Invalid);
+    assert_eq!(EndReason::from_raw(1000), EndReason::App(0));
+    assert_eq!(EndReason::from_raw(1042), EndReason::App(42));
+    assert_eq!(EndReason::from_raw(2007), EndReason::AppException(7));
+    assert_eq!(
+        EndReason::from_raw(
+            ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Local_OfflineMode as _
+        ),
+        EndReason::Local(LocalEndReason::OfflineMode)
+    );
+    assert_eq!(
+        EndReason::from_raw(ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Remote_BadCert as _),
+        EndReason::Remote(RemoteEndReason::BadCert)
+    );
+    assert_eq!(
+        EndReason::from_raw(5999),
+        EndReason::Misc(MiscEndReason::Other(5999))
+    );
+    assert_eq!(EndReason::from_raw(9000), EndReason::Unknown(9000));
+
+    assert!(EndReason::Remote(RemoteEndReason::Timeout).is_timeout());
+    assert!(EndReason::Misc(MiscEndReason::Timeout).is_timeout());
+    assert!(EndReason::AppException(3).is_app());
+    assert!(!EndReason::Local(LocalEndReason::Rights).is_app());
+}
+
+/// Offsets and raw causes outside of their range don't round-trip.
+#[test]
+fn test_end_reason_validity() {
+    assert!(EndReason::App(999).is_valid());
+    assert!(!EndReason::App(1000).is_valid());
+    assert!(!EndReason::AppException(1000).is_valid());
+    assert!(EndReason::Local(LocalEndReason::Other(3999)).is_valid());
+    assert!(!EndReason::Local(LocalEndReason::Other(4000)).is_valid());
+    assert!(!EndReason::Remote(RemoteEndReason::Other(1000)).is_valid());
+    assert!(!EndReason::Misc(MiscEndReason::Other(
+        ESteamNetConnectionEnd::k_ESteamNetConnectionEnd_Misc_Timeout as u32
+    ))
+    .is_valid());
+    assert!(!EndReason::Unknown(1000).is_valid());
+}
+
+/// Only valid application reasons close a connection.
+#[test]
+fn test_close_connection_rejects_invalid_reason() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+
+    for reason in [
+        EndReason::App(1000),
+        EndReason::AppException(1000),
+        EndReason::Remote(RemoteEndReason::BadCert),
+        EndReason::Local(LocalEndReason::Other(5000)),
+    ] {
+        assert!(
+            matches!(
+                left.close_connection(left.connection(), reason, None, false),
+                Err(GnsError::Api(EResult::k_EResultInvalidParam))
+            ),
+            "{reason:?} was accepted"
+        );
+    }
+    left.close_connection(left.connection(), EndReason::AppException(999), None, false)
+        .expect("close_connection failed");
+}
+
+/// The reason and debug string given to `close_connection` by the server
+/// are what the client observes in its `ClosedByPeer` event.
+#[test]
+fn test_close_connection_reason_reaches_peer() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+
+    let mut accepted = None;
+    let mut closed = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while closed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+                accepted = Some(event.connection());
+            }
+        }
+        for event in client.receive_events() {
+            match event.transition() {
+                ConnectionTransition::Connected => {
+                    server
+                        .close_connection(
+                            accepted.expect("client connected before being accepted"),
+                            EndReason::App(42),
+                            Some("kicked"),
+                            false,
+                        )
+                        .expect("close_connection failed");
+                }
+                ConnectionTransition::ClosedByPeer => closed = Some(event.info()),
+                _ => {}
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let info = closed.expect("client never saw the server close");
+    assert_eq!(info.end_reason(), EndReason::App(42));
+    assert_eq!(info.end_debug(), "kicked");
+}
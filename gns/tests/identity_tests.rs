@@ -0,0 +1,150 @@
+//! Tests for `GnsIdentity`: local identity at init, remote identity on
+//! connection info and identity-checked connects. Lives in its own test
+//! binary because the local identity is chosen once per process.
+
+use gns::sys::EResult;
+use gns::{
+    ConnectionTransition, EndReason, GnsConnectionEvent, GnsError, GnsGlobal, GnsIdentity,
+    GnsInitOptions, GnsIpAddr, GnsSocket, IsClient, IsServer,
+};
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+fn global() -> &'static GnsGlobal {
+    GnsGlobal::get_with(GnsInitOptions {
+        identity: Some(GnsIdentity::from_generic_string("test-server").unwrap()),
+        ..Default::default()
+    })
+    .expect("Failed to initialize GNS global")
+}
+
+#[test]
+fn test_identity_parse_and_format() {
+    global();
+
+    let s = GnsIdentity::from_generic_string("lobby").unwrap();
+    assert_eq!(s.generic_string(), Some("lobby"));
+    assert_eq!(s.to_string(), "str:lobby");
+    assert_eq!("str:lobby".parse::<GnsIdentity>().unwrap(), s);
+
+    let b = GnsIdentity::from_generic_bytes(&[0x0a, 0x0b, 0x0c]).unwrap();
+    assert_eq!(b.generic_bytes(), Some(&[0x0a, 0x0b, 0x0c][..]));
+    assert_eq!(b.to_string().parse::<GnsIdentity>().unwrap(), b);
+
+    let address = SocketAddr::from((Ipv4Addr::new(10, 0, 0, 1), 27015));
+    let ip = GnsIdentity::from_ip(address);
+    assert_eq!(ip.ip(), Some(GnsIpAddr::from(address)));
+    assert_eq!(ip.to_string().parse::<GnsIdentity>().unwrap(), ip);
+
+    let steam = GnsIdentity::from_steam_id(76561197960287930);
+    assert_eq!(steam.steam_id(), Some(76561197960287930));
+    assert_ne!(steam, s);
+
+    assert!(GnsIdentity::default().is_invalid());
+    assert!(GnsIdentity::from_generic_string(&"x".repeat(64)).is_err());
+    assert!("nonsense".parse::<GnsIdentity>().is_err());
+}
+
+#[test]
+fn test_local_identity_is_set_at_init() {
+    let gns_global = global();
+    assert_eq!(
+        gns_global.identity(),
+        Some(GnsIdentity::from_generic_string("test-server").unwrap())
+    );
+}
+
+/// Drive a server and an identity-checked client until the client reports
+/// a terminal transition; returns that event with the identity the server saw.
+fn connect_expecting(
+    expected: GnsIdentity,
+    reason: EndReason,
+) -> (
+    GnsConnectionEvent,
+    GnsSocket<IsServer>,
+    GnsSocket<IsClient>,
+    Option<GnsIdentity>,
+) {
+    let gns_global = global();
+    let server = GnsSocket::new(gns_global)
+        .listen_on("127.0.0.1:0")
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect_to_identity(server.local_addr().unwrap(), expected, reason)
+        .expect("Failed to create client socket");
+
+    let mut seen_by_server = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+            }
+            seen_by_server = seen_by_server.or(event.info().remote_identity());
+        }
+        let terminal = client.receive_events().find(|event| {
+            matches!(
+                event.transition(),
+                ConnectionTransition::Connected | ConnectionTransition::Destroyed
+            )
+        });
+        if let Some(event) = terminal {
+            return (event, server, client, seen_by_server);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("client never completed the connection");
+}
+
+#[test]
+fn test_connect_to_expected_identity() {
+    let expected = GnsIdentity::from_generic_string("test-server").unwrap();
+    let (event, _server, _client, seen_by_server) =
+        connect_expecting(expected, EndReason::IDENTITY_MISMATCH);
+    assert_eq!(event.transition(), ConnectionTransition::Connected);
+    assert_eq!(event.info().remote_identity(), Some(expected));
+    // Client and server share the process, hence the identity.
+    assert_eq!(seen_by_server, Some(expected));
+}
+
+#[test]
+fn test_connect_to_unexpected_identity_is_refused() {
+    let expected = GnsIdentity::from_generic_string("someone-else").unwrap();
+    let reason = EndReason::AppException(7);
+    let (event, server, client, _) = connect_expecting(expected, reason);
+    assert_eq!(event.transition(), ConnectionTransition::Destroyed);
+    assert_eq!(event.info().end_reason(), reason);
+    assert_eq!(event.info().end_debug(), "remote identity mismatch");
+    // The connection was closed, not merely reported, and is not tracked.
+    assert!(client.get_connection_info(client.connection()).is_none());
+    assert!(client.connections().is_empty());
+
+    // The server is told the same reason.
+    let gns_global = global();
+    let mut closed = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while closed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        closed = server
+            .receive_events()
+            .find(|event| event.transition() == ConnectionTransition::ClosedByPeer);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let closed = closed.expect("server never saw the client close");
+    assert_eq!(closed.info().end_reason(), reason);
+    assert_eq!(closed.info().end_debug(), "remote identity mismatch");
+}
+
+#[test]
+fn test_connect_to_identity_rejects_non_app_reason() {
+    let gns_global = global();
+    let expected = GnsIdentity::from_generic_string("test-server").unwrap();
+    let result =
+        GnsSocket::new(gns_global).connect_to_identity("127.0.0.1:1", expected, EndReason::Invalid);
+    assert_eq!(
+        result.err(),
+        Some(GnsError::Api(EResult::k_EResultInvalidParam))
+    );
+}
@@ -2,8 +2,9 @@
 //! These tests verify the network communication between server and client instances
 //! using the gns library.
 
-use gns::sys::*;
-use gns::{GnsGlobal, GnsSocket, MessageSlot, SendFlags};
+use gns::{
+    ConnectionState, ConnectionTransition, EndReason, GnsGlobal, GnsSocket, MessageSlot, SendFlags,
+};
 
 use std::{
     collections::HashSet,
@@ -51,10 +52,7 @@ fn run_server(
             for event in server.receive_events() {
                 match (event.old_state(), event.info().state()) {
                     // New connection
-                    (
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                    ) => {
+                    (ConnectionState::None, ConnectionState::Connecting) => {
                         let result = server.accept(event.connection());
                         if result.is_ok() {
                             clients.insert(event.connection());
@@ -62,10 +60,15 @@ fn run_server(
                     },
 
                     // Client disconnected
-                    (_, ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer
-                    | ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally) => {
+                    (_, ConnectionState::ClosedByPeer
+                    | ConnectionState::ProblemDetectedLocally) => {
                         clients.remove(&event.connection());
-                        let _ = server.close_connection(event.connection(), 0, None, false);
+                        let _ = server.close_connection(
+                            event.connection(),
+                            EndReason::App(0),
+                            None,
+                            false,
+                        );
                     },
 
                     _ => {}
@@ -122,8 +125,8 @@ fn run_client(
             gns_global.poll_callbacks();
 
             for event in client.receive_events() {
-                if event.old_state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting
-                   && event.info().state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected {
+                if event.old_state() == ConnectionState::Connecting
+                   && event.info().state() == ConnectionState::Connected {
                     connected = true;
                 }
             }
@@ -349,10 +352,7 @@ fn test_client_connection_and_disconnection() {
     let port = free_port();
 
     // Track connection events
-    let connection_events = Arc::new(Mutex::new(Vec::<(
-        ESteamNetworkingConnectionState,
-        ESteamNetworkingConnectionState,
-    )>::new()));
+    let connection_events = Arc::new(Mutex::new(Vec::<(ConnectionState, ConnectionState)>::new()));
     let server_ready = Arc::new(Barrier::new(2));
     let server_done = Arc::new(Mutex::new(false));
 
@@ -386,11 +386,7 @@ fn test_client_connection_and_disconnection() {
                     .unwrap()
                     .push((event.old_state(), event.info().state()));
 
-                if let (
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                    ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                ) = (event.old_state(), event.info().state())
-                {
+                if event.transition() == ConnectionTransition::IncomingRequest {
                     let result = server.accept(event.connection());
                     assert!(result.is_ok(), "Failed to accept connection");
                 }
@@ -420,8 +416,8 @@ fn test_client_connection_and_disconnection() {
             gns_global.poll_callbacks();
 
             for event in client.receive_events() {
-                if event.old_state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting
-                   && event.info().state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected {
+                if event.old_state() == ConnectionState::Connecting
+                   && event.info().state() == ConnectionState::Connected {
                     connected = true;
                 }
             }
@@ -446,9 +442,9 @@ fn test_client_connection_and_disconnection() {
         let events = connection_events.lock().unwrap();
         for (_old, new) in events.iter() {
             // Check for any type of closed state
-            if *new == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer ||
-               *new == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally ||
-               *new == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None {
+            if *new == ConnectionState::ClosedByPeer ||
+               *new == ConnectionState::ProblemDetectedLocally ||
+               *new == ConnectionState::None {
                 disconnect_detected = true;
                 break;
             }
@@ -0,0 +1,95 @@
+//! Tests for `GnsIpAddr` conversions and `ToSocketAddrs`-based addressing.
+
+use gns::{ConnectionTransition, GnsError, GnsGlobal, GnsIpAddr, GnsSocket};
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_ip_addr_socket_addr_round_trip() {
+    for address in [
+        SocketAddr::from((Ipv4Addr::new(192, 168, 1, 20), 27015)),
+        SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, 443)),
+        SocketAddr::from((Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 65535)),
+    ] {
+        let gns = GnsIpAddr::from(address);
+        assert_eq!(gns.ip(), address.ip());
+        assert_eq!(gns.port(), address.port());
+        assert_eq!(SocketAddr::from(gns), address);
+    }
+}
+
+#[test]
+fn test_ip_addr_parse_and_format() {
+    // String conversions go through GNS, which needs the library loaded.
+    GnsGlobal::get().expect("Failed to initialize GNS global");
+
+    let v4: GnsIpAddr = "127.0.0.1:27015".parse().expect("parse v4");
+    assert_eq!(v4, GnsIpAddr::new(Ipv4Addr::LOCALHOST.into(), 27015));
+    assert_eq!(v4.to_string(), "127.0.0.1:27015");
+
+    let v6: GnsIpAddr = "[::1]:8080".parse().expect("parse v6");
+    assert_eq!(
+        SocketAddr::from(v6),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, 8080))
+    );
+    assert_eq!(v6.to_string(), "[::1]:8080");
+
+    let no_port: GnsIpAddr = "10.0.0.1".parse().expect("parse without port");
+    assert_eq!(no_port.port(), 0);
+
+    assert!(matches!(
+        "not an address".parse::<GnsIpAddr>(),
+        Err(GnsError::Address(_))
+    ));
+}
+
+/// A server bound through `listen_on` is reachable through an address string
+/// given to `connect_to`, and both ends report socket addresses.
+#[test]
+fn test_listen_on_and_connect_to() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let server = GnsSocket::new(gns_global)
+        .listen_on("127.0.0.1:0")
+        .expect("Failed to create server socket");
+    let port = server.local_addr().expect("local_addr failed").port();
+    let client = GnsSocket::new(gns_global)
+        .connect_to(format!("127.0.0.1:{port}"))
+        .expect("Failed to create client socket");
+
+    let mut peer = None;
+    let mut connected = false;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !connected && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+                peer = Some(event.info().remote_socket_addr());
+            }
+        }
+        connected = client
+            .receive_events()
+            .any(|event| event.transition() == ConnectionTransition::Connected);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(connected, "client did not connect within the timeout");
+    let peer = peer.expect("server never saw the client");
+    assert!(peer.ip().is_loopback());
+    assert_ne!(peer.port(), 0);
+
+    let info = client
+        .get_connection_info(client.connection())
+        .expect("connection info");
+    assert_eq!(info.remote_socket_addr().port(), port);
+}
+
+#[test]
+fn test_unresolvable_address_is_reported() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    assert!(matches!(
+        GnsSocket::new(gns_global).connect_to("no-port-given"),
+        Err(GnsError::Address(_))
+    ));
+}
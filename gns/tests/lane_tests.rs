@@ -1,8 +1,7 @@
 //! Tests for GNS connection lane configuration and quality of service
 //! These tests verify the lane configuration functionality for prioritizing different types of traffic
 
-use gns::sys::*;
-use gns::{GnsGlobal, GnsLane, GnsSocket, SendFlags};
+use gns::{ConnectionState, EndReason, GnsGlobal, GnsLane, GnsSocket, SendFlags};
 
 use std::{
     collections::HashMap,
@@ -68,10 +67,7 @@ fn test_connection_lane_configuration() {
             for event in server.receive_events() {
                 match (event.old_state(), event.info().state()) {
                     // New connection
-                    (
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                    ) => {
+                    (ConnectionState::None, ConnectionState::Connecting) => {
                         let result = server.accept(event.connection());
                         if result.is_ok() {
                             client_connection = Some(event.connection());
@@ -79,12 +75,17 @@ fn test_connection_lane_configuration() {
                     },
 
                     // Client disconnected
-                    (_, ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer
-                    | ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally) => {
+                    (_, ConnectionState::ClosedByPeer
+                    | ConnectionState::ProblemDetectedLocally) => {
                         if Some(event.connection()) == client_connection {
                             client_connection = None;
                         }
-                        let _ = server.close_connection(event.connection(), 0, None, false);
+                        let _ = server.close_connection(
+                            event.connection(),
+                            EndReason::App(0),
+                            None,
+                            false,
+                        );
                     },
 
                     _ => {}
@@ -132,8 +133,8 @@ fn test_connection_lane_configuration() {
             gns_global.poll_callbacks();
 
             for event in client.receive_events() {
-                if event.old_state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting
-                   && event.info().state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected {
+                if event.old_state() == ConnectionState::Connecting
+                   && event.info().state() == ConnectionState::Connected {
                     connected = true;
                 }
             }
@@ -316,10 +317,7 @@ fn test_get_connection_real_time_lane_status() {
             for event in server.receive_events() {
                 match (event.old_state(), event.info().state()) {
                     // New connection
-                    (
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                    ) => {
+                    (ConnectionState::None, ConnectionState::Connecting) => {
                         let result = server.accept(event.connection());
                         if result.is_ok() {
                             *client_conn_clone.lock().unwrap() = Some(event.connection());
@@ -327,20 +325,22 @@ fn test_get_connection_real_time_lane_status() {
                     },
 
                     // Client disconnected
-                    (_, ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ClosedByPeer
-                    | ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_ProblemDetectedLocally) => {
+                    (_, ConnectionState::ClosedByPeer
+                    | ConnectionState::ProblemDetectedLocally) => {
                         let mut conn = client_conn_clone.lock().unwrap();
                         if Some(event.connection()) == *conn {
                             *conn = None;
                         }
-                        let _ = server.close_connection(event.connection(), 0, None, false);
+                        let _ = server.close_connection(
+                            event.connection(),
+                            EndReason::App(0),
+                            None,
+                            false,
+                        );
                     },
 
                     // Client is now connected
-                    (
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected,
-                    ) => {
+                    (ConnectionState::Connecting, ConnectionState::Connected) => {
                         // Check for lane status after connection is established
                         if let Some(conn) = *client_conn_clone.lock().unwrap() {
                             // Give the connection some time to fully establish
@@ -401,8 +401,8 @@ fn test_get_connection_real_time_lane_status() {
             gns_global.poll_callbacks();
 
             for event in client.receive_events() {
-                if event.old_state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting
-                   && event.info().state() == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected {
+                if event.old_state() == ConnectionState::Connecting
+                   && event.info().state() == ConnectionState::Connected {
                     connected = true;
                 }
             }
@@ -481,7 +481,7 @@ fn test_get_connection_real_time_lane_status() {
         // Overall connection status should be connected
         assert_eq!(
             status.state(),
-            ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected,
+            ConnectionState::Connected,
             "Connection is not in connected state"
         );
     }
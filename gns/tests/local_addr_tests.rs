@@ -0,0 +1,68 @@
+//! Tests for listening on port 0 and querying the bound address.
+
+use gns::{ConnectionTransition, GnsError, GnsGlobal, GnsSocket};
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// Several servers listening on port 0 each get their own real port, which
+/// clients can then connect to.
+#[test]
+fn test_listen_on_port_zero_reports_bound_port() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let servers: Vec<_> = (0..3)
+        .map(|_| {
+            GnsSocket::new(gns_global)
+                .listen(Ipv4Addr::LOCALHOST.into(), 0)
+                .expect("Failed to create server socket")
+        })
+        .collect();
+    let addrs: Vec<SocketAddr> = servers
+        .iter()
+        .map(|server| server.local_addr().expect("local_addr failed"))
+        .collect();
+    for addr in &addrs {
+        assert_eq!(addr.ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+        assert_ne!(addr.port(), 0);
+    }
+    assert!(addrs[0].port() != addrs[1].port() && addrs[1].port() != addrs[2].port());
+
+    let server = &servers[1];
+    let client = GnsSocket::new(gns_global)
+        .connect(addrs[1].ip(), addrs[1].port())
+        .expect("Failed to create client socket");
+    let mut connected = false;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !connected && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+            }
+        }
+        connected = client
+            .receive_events()
+            .any(|event| event.transition() == ConnectionTransition::Connected);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(connected, "client did not connect to the reported port");
+}
+
+#[test]
+fn test_listener_addr() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), 0)
+        .expect("Failed to create server socket");
+    let added = server
+        .add_listener(Ipv4Addr::LOCALHOST.into(), 0)
+        .expect("Failed to add listener");
+    let addr = server.listener_addr(added).expect("listener_addr failed");
+    assert_ne!(addr.port(), 0);
+    assert_ne!(addr, server.local_addr().expect("local_addr failed"));
+
+    server
+        .remove_listener(added)
+        .expect("remove_listener failed");
+    assert_eq!(server.listener_addr(added), Err(GnsError::UnknownListener));
+}
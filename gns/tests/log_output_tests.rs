@@ -0,0 +1,76 @@
+//! The debug output forwarded to `log`, see `GnsUtils::enable_log_output`.
+#![cfg(feature = "log")]
+
+use gns::sys::*;
+use gns::{GnsGlobal, GnsSocket};
+
+use log::{Log, Metadata, Record};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Target and `connection_name` of a record.
+type Collected = (String, Option<String>);
+
+/// Logger keeping every record.
+struct Collector(Mutex<Vec<Collected>>);
+
+impl Log for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let name = record
+            .key_values()
+            .get("connection_name".into())
+            .map(|value| value.to_string());
+        self.0
+            .lock()
+            .unwrap()
+            .push((record.target().to_owned(), name));
+    }
+
+    fn flush(&self) {}
+}
+
+static COLLECTOR: Collector = Collector(Mutex::new(Vec::new()));
+
+/// Lines about a named connection carry its name as a key.
+#[test]
+fn test_log_output_with_connection_name() {
+    log::set_logger(&COLLECTOR).expect("Failed to set the logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    gns_global.utils().enable_log_output(
+        ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_Everything,
+    );
+
+    let named = |records: &[Collected]| {
+        records
+            .iter()
+            .any(|(_, name)| name.as_deref() == Some("log-test"))
+    };
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !named(&COLLECTOR.0.lock().unwrap()) && Instant::now() < deadline {
+        let (left, right) = GnsSocket::new(gns_global)
+            .socket_pair(true)
+            .expect("Failed to create socket pair");
+        left.set_connection_name(left.connection(), "log-test")
+            .expect("set_connection_name failed");
+        drop((left, right));
+        for _ in 0..10 {
+            gns_global.poll_callbacks();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+    gns_global.utils().disable_debug_output();
+
+    let records = COLLECTOR.0.lock().unwrap();
+    assert!(!records.is_empty(), "no record was logged");
+    assert!(
+        records.iter().all(|(target, _)| target == "gns"),
+        "a record was logged out of the gns target"
+    );
+    assert!(named(&records), "no record carried the connection name");
+}
@@ -0,0 +1,94 @@
+//! Tests for manual poll mode: no GNS service thread, the network is stepped
+//! explicitly with `GnsGlobal::poll`. Lives in its own test binary because
+//! the mode is chosen once per process.
+
+use gns::{
+    ConnectionTransition, GnsGlobal, GnsInitOptions, GnsSocket, IsClient, IsServer, SendFlags,
+};
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+const MANUAL: GnsInitOptions = GnsInitOptions {
+    manual_poll: true,
+    identity: None,
+};
+
+fn global() -> &'static GnsGlobal {
+    GnsGlobal::get_with(MANUAL).expect("Failed to initialize GNS global")
+}
+
+/// Step the network until `done` returns `true` or the deadline expires.
+fn step_until(gns_global: &GnsGlobal, mut done: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        gns_global.poll(Duration::from_millis(5));
+        gns_global.poll_callbacks();
+        if done() {
+            return true;
+        }
+    }
+    false
+}
+
+fn connected_pair(gns_global: &'static GnsGlobal) -> (GnsSocket<IsServer>, GnsSocket<IsClient>) {
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let client = GnsSocket::new(gns_global)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+    let connected = step_until(gns_global, || {
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+            }
+        }
+        client
+            .receive_events()
+            .any(|event| event.transition() == ConnectionTransition::Connected)
+    });
+    assert!(connected, "client did not connect within the timeout");
+    (server, client)
+}
+
+#[test]
+fn test_manual_poll_exchanges_messages() {
+    let gns_global = global();
+    assert!(gns_global.options().manual_poll);
+    let (server, client) = connected_pair(gns_global);
+
+    let msg = gns_global.utils().allocate_message(
+        client.connection(),
+        SendFlags::RELIABLE,
+        &b"stepped"[..],
+    );
+    client.send_message(msg).expect("send_message failed");
+
+    let mut payload = None;
+    let received = step_until(gns_global, || {
+        if let Some(message) = server
+            .receive_messages::<1>()
+            .expect("receive failed")
+            .next()
+        {
+            payload = Some(message.payload().to_vec());
+        }
+        payload.is_some()
+    });
+    assert!(received, "message not received within the timeout");
+    assert_eq!(payload.as_deref(), Some(&b"stepped"[..]));
+}
+
+/// Once initialized, the global can be retrieved with `get` or the same
+/// options, but not re-initialized with other options.
+#[test]
+fn test_init_options_are_fixed_once_initialized() {
+    let gns_global = global();
+    assert!(core::ptr::eq(GnsGlobal::get().unwrap(), gns_global));
+    assert!(GnsGlobal::get_with(GnsInitOptions::default()).is_err());
+}
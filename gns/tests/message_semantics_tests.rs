@@ -4,8 +4,10 @@
 //! - `m_nUserData` round-trips through the wrapper untouched,
 //! - `send_messages` returns failed messages in `SendOutcome::Failed`.
 
-use gns::sys::*;
-use gns::{GnsConnection, GnsGlobal, GnsSocket, Payload, SendFlags, SendOutcome};
+use gns::{
+    ConnectionState, ConnectionTransition, GnsConnection, GnsGlobal, GnsSocket, Payload, SendFlags,
+    SendOutcome,
+};
 
 use std::{
     net::Ipv4Addr,
@@ -284,11 +286,7 @@ fn test_send_messages_mixed_success_and_failure() {
             while !*server_done.lock().unwrap() {
                 gns_global.poll_callbacks();
                 for event in server.receive_events() {
-                    if let (
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                        ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-                    ) = (event.old_state(), event.info().state())
-                    {
+                    if event.transition() == ConnectionTransition::IncomingRequest {
                         let _ = server.accept(event.connection());
                     }
                 }
@@ -312,10 +310,8 @@ fn test_send_messages_mixed_success_and_failure() {
     while !connected && start.elapsed() < Duration::from_secs(5) {
         gns_global.poll_callbacks();
         for event in client.receive_events() {
-            if event.old_state()
-                == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting
-                && event.info().state()
-                    == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected
+            if event.old_state() == ConnectionState::Connecting
+                && event.info().state() == ConnectionState::Connected
             {
                 connected = true;
             }
@@ -0,0 +1,232 @@
+//! Tests for the connectionless `GnsMessages` interface (`p2p` feature).
+//! Sessions need a peer with another identity, chosen once per process, so
+//! the exchange test runs its peer in a child process and signals to it
+//! over loopback UDP.
+#![cfg(feature = "p2p")]
+
+use gns::sys::*;
+use gns::{
+    GnsConfig, GnsConnection, GnsConnectionInfo, GnsError, GnsGlobal, GnsIdentity, GnsInitOptions,
+    GnsMessagesEvent, SendFlags, Signaling, SignalingFactory, SignalingRecvContext,
+};
+
+use std::net::{SocketAddr, UdpSocket};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Address of the test process, set for the child running [`peer_process`].
+const PEER_ENV: &str = "GNS_MESSAGES_TEST_PEER";
+const CHANNEL: i32 = 3;
+
+/// Serializes the tests of this binary, they share the session events.
+static SUITE_LOCK: Mutex<()> = Mutex::new(());
+
+fn identity(name: &str) -> GnsIdentity {
+    GnsIdentity::from_generic_string(name).unwrap()
+}
+
+fn global(name: &str) -> &'static GnsGlobal {
+    let gns_global = GnsGlobal::get_with(GnsInitOptions {
+        identity: Some(identity(name)),
+        ..Default::default()
+    })
+    .expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+    for (key, value) in [
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_P2P_Transport_ICE_Enable,
+            GnsConfig::Int32(k_nSteamNetworkingConfig_P2P_Transport_ICE_Enable_Private as _),
+        ),
+        // No STUN server: host candidates are enough on a single machine.
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_P2P_STUN_ServerList,
+            GnsConfig::String(""),
+        ),
+        // Neither peer has a certificate.
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_IP_AllowWithoutAuth,
+            GnsConfig::Int32(2),
+        ),
+        // Notice the peer process is gone quickly.
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_TimeoutConnected,
+            GnsConfig::Int32(2000),
+        ),
+    ] {
+        utils
+            .set_global_config_value(key, value)
+            .expect("set_global_config_value failed");
+    }
+    gns_global
+}
+
+/// Signaling channel to the other test process, over loopback UDP.
+#[derive(Clone)]
+struct UdpSignaling {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+}
+
+impl UdpSignaling {
+    fn bind() -> Arc<UdpSocket> {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind signaling socket");
+        socket.set_nonblocking(true).unwrap();
+        Arc::new(socket)
+    }
+
+    /// Hand the signals received on `socket` to GNS, answering each one
+    /// through its sender.
+    fn deliver(global: &GnsGlobal, socket: &Arc<UdpSocket>) {
+        let mut signal = vec![0u8; 65536];
+        while let Ok((len, peer)) = socket.recv_from(&mut signal) {
+            let mut context = UdpSignaling {
+                socket: socket.clone(),
+                peer,
+            };
+            global.received_signal(&signal[..len], &mut context);
+        }
+    }
+}
+
+impl Signaling for UdpSignaling {
+    fn send_signal(&self, _: GnsConnection, _: &GnsConnectionInfo, signal: &[u8]) -> bool {
+        self.socket.send_to(signal, self.peer).is_ok()
+    }
+}
+
+impl SignalingRecvContext for UdpSignaling {
+    fn on_connect_request(
+        &mut self,
+        _: GnsConnection,
+        _: &GnsIdentity,
+        _: i32,
+    ) -> Option<Box<dyn Signaling>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl SignalingFactory for UdpSignaling {
+    fn create_signaling(&self, _: &GnsIdentity, _: i32, _: i32) -> Option<Box<dyn Signaling>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+#[test]
+fn test_messages_without_session() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = global("messages-test");
+    let messages = gns_global.messages();
+    let stranger = identity("nobody");
+
+    assert_eq!(
+        messages
+            .receive_messages_on_channel::<16>(0)
+            .expect("receive_messages_on_channel failed")
+            .len(),
+        0
+    );
+    assert!(messages.session_info(&stranger).is_none());
+    assert_eq!(messages.accept_session(&stranger), Err(GnsError::Session));
+    assert_eq!(messages.close_session(&stranger), Err(GnsError::Session));
+    assert_eq!(messages.close_channel(&stranger, 3), Err(GnsError::Session));
+
+    gns_global.poll_callbacks();
+    let events: Vec<_> = messages.receive_events().collect();
+    assert!(events.is_empty(), "unexpected session events: {events:?}");
+}
+
+/// The peer of [`test_message_exchange_with_peer_process`]: sends `ping` to
+/// the test process and returns, leaving its session dangling, once
+/// answered with `pong`.
+#[test]
+#[ignore = "run by test_message_exchange_with_peer_process"]
+fn peer_process() {
+    let Ok(test_process) = std::env::var(PEER_ENV) else {
+        return;
+    };
+    let gns_global = global("messages-peer");
+    let messages = gns_global.messages();
+    let socket = UdpSignaling::bind();
+    messages.set_signaling(UdpSignaling {
+        socket: socket.clone(),
+        peer: test_process.parse().unwrap(),
+    });
+
+    let tester = identity("messages-test");
+    messages
+        .send_message_to_user(&tester, SendFlags::RELIABLE, CHANNEL, b"ping")
+        .expect("send_message_to_user failed");
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        UdpSignaling::deliver(gns_global, &socket);
+        for message in messages
+            .receive_messages_on_channel::<16>(CHANNEL)
+            .expect("receive_messages_on_channel failed")
+        {
+            if message.payload() == b"pong" && message.peer_identity() == tester {
+                return;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("no answer from the test process");
+}
+
+/// A peer process messages us by identity: its session must be accepted,
+/// its message arrives on its channel, and our answer reaches it. Once it
+/// exits, the session fails.
+#[test]
+fn test_message_exchange_with_peer_process() {
+    let _guard = SUITE_LOCK.lock().unwrap();
+    let gns_global = global("messages-test");
+    let messages = gns_global.messages();
+    let socket = UdpSignaling::bind();
+    let mut peer_process = Command::new(std::env::current_exe().unwrap())
+        .args(["peer_process", "--exact", "--ignored"])
+        .env(PEER_ENV, socket.local_addr().unwrap().to_string())
+        .spawn()
+        .expect("Failed to spawn the peer process");
+
+    let peer = identity("messages-peer");
+    let (mut requested, mut pinged, mut failed) = (false, false, None);
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while failed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        UdpSignaling::deliver(gns_global, &socket);
+        for event in messages.receive_events() {
+            match event {
+                GnsMessagesEvent::SessionRequest(identity) => {
+                    assert_eq!(identity, peer);
+                    messages
+                        .accept_session(&identity)
+                        .expect("accept_session failed");
+                    requested = true;
+                }
+                GnsMessagesEvent::SessionFailed(info) => failed = Some(info),
+            }
+        }
+        for message in messages
+            .receive_messages_on_channel::<16>(CHANNEL)
+            .expect("receive_messages_on_channel failed")
+        {
+            assert_eq!(message.payload(), b"ping");
+            assert_eq!(message.peer_identity(), peer);
+            assert_eq!(message.channel(), CHANNEL);
+            pinged = true;
+            messages
+                .send_message_to_user(&peer, SendFlags::RELIABLE, CHANNEL, b"pong")
+                .expect("send_message_to_user failed");
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let status = peer_process.wait().expect("peer process vanished");
+    assert!(status.success(), "peer process failed: {status}");
+    assert!(requested, "no session request from the peer");
+    assert!(pinged, "no message from the peer");
+    let failed = failed.expect("session did not fail once the peer exited");
+    assert_eq!(failed.remote_identity(), Some(peer));
+}
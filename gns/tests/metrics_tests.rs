@@ -0,0 +1,173 @@
+//! The statistics and counters reported to `metrics`, see
+//! `GnsMetricsSampler`.
+#![cfg(feature = "metrics")]
+
+use gns::{GnsGlobal, GnsMetricsSampler, GnsSocket, SendFlags};
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Recorder keeping the last value of every counter and gauge.
+#[derive(Default)]
+struct Collector(Mutex<HashMap<Key, Arc<AtomicU64>>>);
+
+impl Collector {
+    fn handle(&self, key: &Key) -> Arc<AtomicU64> {
+        Arc::clone(self.0.lock().unwrap().entry(key.clone()).or_default())
+    }
+
+    /// Keys named `name` with the label `label`.
+    fn find(&self, name: &str, label: (&str, &str)) -> Vec<(Key, u64)> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| {
+                key.name() == name
+                    && key
+                        .labels()
+                        .any(|l| l.key() == label.0 && l.value() == label.1)
+            })
+            .map(|(key, value)| (key.clone(), value.load(Ordering::SeqCst)))
+            .collect()
+    }
+
+    fn counter(&self, name: &str) -> u64 {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.name() == name)
+            .map(|(_, value)| value.load(Ordering::SeqCst))
+            .sum()
+    }
+}
+
+impl Recorder for Collector {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.handle(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.handle(key))
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn test_sampler_and_message_counters() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let collector = Collector::default();
+    let (left, right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+
+    metrics::with_local_recorder(&collector, || {
+        let msg = gns_global.utils().allocate_message(
+            left.connection(),
+            SendFlags::RELIABLE,
+            &b"counted"[..],
+        );
+        left.send_message(msg).expect("send_message failed");
+
+        let mut received = 0;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while received == 0 && Instant::now() < deadline {
+            gns_global.poll_callbacks();
+            received += right
+                .receive_messages::<1>()
+                .expect("receive failed")
+                .count();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(received, 1, "message not received within the timeout");
+
+        // The lane is not configured, the sampler falls back to the
+        // connection statistics.
+        let reported = GnsMetricsSampler::new().with_lanes(4).sample(&left);
+        assert_eq!(reported, 1);
+    });
+
+    assert_eq!(collector.counter("gns_messages_sent_total"), 1);
+    assert_eq!(collector.counter("gns_messages_received_total"), 1);
+    assert_eq!(collector.counter("gns_send_failures_total"), 0);
+
+    let id = left.connection().into_raw().to_string();
+    let id = id.as_str();
+    for name in [
+        "gns_connection_ping_ms",
+        "gns_connection_quality_local",
+        "gns_connection_send_rate_bytes_per_sec",
+        "gns_connection_pending_reliable_bytes",
+        "gns_connection_queue_time_seconds",
+    ] {
+        assert_eq!(
+            collector.find(name, ("connection", id)).len(),
+            1,
+            "{name} was not reported for the connection"
+        );
+    }
+    assert!(collector
+        .find("gns_lane_queue_time_seconds", ("connection", id))
+        .is_empty());
+}
+
+/// Forgetting a connection zeroes every gauge reported for it.
+#[test]
+fn test_sampler_forget() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let collector = Collector::default();
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+    let sampler = GnsMetricsSampler::new();
+    let id = left.connection().into_raw().to_string();
+    let id = id.as_str();
+
+    metrics::with_local_recorder(&collector, || {
+        assert!(sampler.sample_connection(&left, left.connection()));
+    });
+    let sampled = collector.find("gns_connection_quality_local", ("connection", id));
+    assert_ne!(sampled[0].1, 0, "quality not reported");
+
+    metrics::with_local_recorder(&collector, || sampler.forget(left.connection()));
+    for (key, value) in collector
+        .find("gns_connection_ping_ms", ("connection", id))
+        .into_iter()
+        .chain(collector.find("gns_connection_quality_local", ("connection", id)))
+    {
+        assert_eq!(f64::from_bits(value), 0.0, "{} not zeroed", key.name());
+    }
+}
+
+/// Sends GNS rejects are counted by result.
+#[test]
+fn test_send_failure_counter() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let collector = Collector::default();
+    metrics::with_local_recorder(&collector, || {
+        let msg = gns_global.utils().allocate_message(
+            gns::GnsConnection::default(),
+            SendFlags::RELIABLE,
+            &b"nowhere"[..],
+        );
+        let (left, _right) = GnsSocket::new(gns_global)
+            .socket_pair(false)
+            .expect("Failed to create socket pair");
+        assert!(left.send_message(msg).is_err());
+    });
+    assert_eq!(collector.counter("gns_send_failures_total"), 1);
+    assert_eq!(collector.counter("gns_messages_sent_total"), 0);
+}
@@ -0,0 +1,101 @@
+//! Tests for servers owning several listen sockets that share one poll
+//! group and event queue.
+
+use gns::{ConnectionTransition, GnsError, GnsGlobal, GnsSocket, IsClient, SendFlags};
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+/// Clients of two listeners are accepted through the same event queue, each
+/// tagged with its listener, and their messages land in the same poll group.
+#[test]
+fn test_listeners_share_queue_and_poll_group() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (port_a, port_b) = (free_port(), free_port());
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port_a)
+        .expect("Failed to create server socket");
+    let listener_a = server.listeners()[0];
+    let listener_b = server
+        .add_listener(Ipv4Addr::LOCALHOST.into(), port_b)
+        .expect("Failed to add listener");
+    assert_eq!(server.listeners(), vec![listener_a, listener_b]);
+
+    let connect = |port| -> GnsSocket<IsClient> {
+        GnsSocket::new(gns_global)
+            .connect(Ipv4Addr::LOCALHOST.into(), port)
+            .expect("Failed to create client socket")
+    };
+    let clients = [connect(port_a), connect(port_b)];
+
+    let mut accepted = HashMap::new();
+    let mut connected = 0;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while connected < clients.len() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+                accepted.insert(event.info().remote_port(), event.listen_socket());
+            }
+        }
+        for client in &clients {
+            connected += client
+                .receive_events()
+                .filter(|event| event.transition() == ConnectionTransition::Connected)
+                .count();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(connected, clients.len(), "clients did not connect in time");
+    assert_eq!(accepted.len(), 2);
+    let mut listeners: Vec<_> = accepted.into_values().collect();
+    listeners.sort();
+    let mut expected = vec![Some(listener_a), Some(listener_b)];
+    expected.sort();
+    assert_eq!(listeners, expected);
+
+    for client in &clients {
+        let msg = gns_global.utils().allocate_message(
+            client.connection(),
+            SendFlags::RELIABLE,
+            &b"hi"[..],
+        );
+        client.send_message(msg).expect("send_message failed");
+    }
+    let mut received = 0;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while received < clients.len() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        received += server
+            .receive_messages::<10>()
+            .expect("receive failed")
+            .len();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(received, clients.len());
+}
+
+#[test]
+fn test_remove_listener() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), free_port())
+        .expect("Failed to create server socket");
+    let added = server
+        .add_listener(Ipv4Addr::LOCALHOST.into(), free_port())
+        .expect("Failed to add listener");
+
+    server
+        .remove_listener(added)
+        .expect("remove_listener failed");
+    assert_eq!(server.listeners().len(), 1);
+    assert_eq!(
+        server.remove_listener(added),
+        Err(GnsError::UnknownListener)
+    );
+}
@@ -0,0 +1,111 @@
+//! Tests for P2P connections (`p2p` feature): two peer sockets of this
+//! process rendezvous through `LocalSignaling`, with ICE restricted to local
+//! candidates so that no STUN or signaling service is involved. Lives in its
+//! own test binary because P2P requires a local identity, chosen once per
+//! process.
+#![cfg(feature = "p2p")]
+
+use gns::sys::*;
+use gns::{
+    ConnectionTransition, GnsConfig, GnsGlobal, GnsIdentity, GnsInitOptions, GnsSocket,
+    LocalSignaling, SendFlags,
+};
+
+use std::time::{Duration, Instant};
+
+fn global() -> &'static GnsGlobal {
+    let gns_global = GnsGlobal::get_with(GnsInitOptions {
+        identity: Some(GnsIdentity::from_generic_string("p2p-peer").unwrap()),
+        ..Default::default()
+    })
+    .expect("Failed to initialize GNS global");
+    let utils = gns_global.utils();
+    for (key, value) in [
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_P2P_Transport_ICE_Enable,
+            GnsConfig::Int32(k_nSteamNetworkingConfig_P2P_Transport_ICE_Enable_Private as _),
+        ),
+        // No STUN server: host candidates are enough on a single machine.
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_P2P_STUN_ServerList,
+            GnsConfig::String(""),
+        ),
+        // Neither peer has a certificate.
+        (
+            ESteamNetworkingConfigValue::k_ESteamNetworkingConfig_IP_AllowWithoutAuth,
+            GnsConfig::Int32(2),
+        ),
+    ] {
+        utils
+            .set_global_config_value(key, value)
+            .expect("set_global_config_value failed");
+    }
+    gns_global
+}
+
+#[test]
+fn test_peer_rejects_virtual_port_in_use() {
+    let gns_global = global();
+    let _peer = GnsSocket::new(gns_global)
+        .peer(7)
+        .expect("Failed to create peer socket");
+    assert!(GnsSocket::new(gns_global).peer(7).is_err());
+}
+
+#[test]
+fn test_p2p_connect_over_local_signaling() {
+    let gns_global = global();
+    let identity = gns_global.identity().expect("local identity not set");
+    let initiator = GnsSocket::new(gns_global)
+        .peer(0)
+        .expect("Failed to create initiating peer");
+    let responder = GnsSocket::new(gns_global)
+        .peer(1)
+        .expect("Failed to create responding peer");
+    assert_eq!(responder.local_virtual_port(), 1);
+
+    let (initiator_end, responder_end) = LocalSignaling::pair();
+    let connection = initiator
+        .connect_p2p(&identity, 1, initiator_end.clone())
+        .expect("connect_p2p failed");
+
+    let mut received = None;
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while received.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        initiator_end.deliver(gns_global);
+        responder_end.deliver(gns_global);
+        for event in responder.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                assert_eq!(event.listen_socket(), Some(responder.listen_socket()));
+                responder.accept(event.connection()).expect("accept failed");
+            }
+        }
+        for event in initiator.receive_events() {
+            match event.transition() {
+                ConnectionTransition::Connected => {
+                    assert_eq!(event.connection(), connection);
+                    let msg = gns_global.utils().allocate_message(
+                        connection,
+                        SendFlags::RELIABLE,
+                        &b"hello peer"[..],
+                    );
+                    initiator.send_message(msg).expect("send_message failed");
+                }
+                ConnectionTransition::ClosedByPeer
+                | ConnectionTransition::ProblemDetectedLocally => {
+                    panic!("P2P connection failed: {}", event.info().end_debug())
+                }
+                _ => {}
+            }
+        }
+        received = responder
+            .receive_messages::<1>()
+            .expect("receive_messages failed")
+            .next();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let message = received.expect("no message over the P2P connection");
+    assert_eq!(message.payload(), b"hello peer");
+}
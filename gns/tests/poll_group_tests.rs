@@ -0,0 +1,120 @@
+//! Tests for sharding accepted connections across `GnsPollGroup`s polled by
+//! their own worker threads.
+
+use gns::sys::EResult;
+use gns::{
+    ConnectionTransition, GnsConnection, GnsError, GnsGlobal, GnsSocket, IsClient, SendFlags,
+};
+
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+#[test]
+fn test_poll_groups_shard_connections_across_threads() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let clients: Vec<GnsSocket<IsClient>> = (0..4)
+        .map(|_| {
+            GnsSocket::new(gns_global)
+                .connect(Ipv4Addr::LOCALHOST.into(), port)
+                .expect("Failed to create client socket")
+        })
+        .collect();
+
+    let mut accepted = Vec::new();
+    let mut connected = 0;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while connected < clients.len() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                server.accept(event.connection()).expect("accept failed");
+                accepted.push(event.connection());
+            }
+        }
+        for client in &clients {
+            connected += client
+                .receive_events()
+                .filter(|event| event.transition() == ConnectionTransition::Connected)
+                .count();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(connected, clients.len(), "clients did not connect in time");
+
+    // Shard the server side of the connections over two groups.
+    let groups = [
+        gns_global
+            .create_poll_group()
+            .expect("create_poll_group failed"),
+        gns_global
+            .create_poll_group()
+            .expect("create_poll_group failed"),
+    ];
+    let mut shards: [HashSet<GnsConnection>; 2] = Default::default();
+    for (i, &connection) in accepted.iter().enumerate() {
+        groups[i % 2].attach(connection).expect("attach failed");
+        shards[i % 2].insert(connection);
+    }
+
+    for client in &clients {
+        let msg = gns_global.utils().allocate_message(
+            client.connection(),
+            SendFlags::RELIABLE,
+            &b"sharded"[..],
+        );
+        client.send_message(msg).expect("send_message failed");
+    }
+
+    let received: Vec<HashSet<GnsConnection>> = std::thread::scope(|scope| {
+        let workers: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                scope.spawn(move || {
+                    let mut seen = HashSet::new();
+                    let deadline = Instant::now() + Duration::from_secs(10);
+                    while seen.len() < 2 && Instant::now() < deadline {
+                        gns_global.poll_callbacks();
+                        for message in group.receive_messages::<10>().expect("receive failed") {
+                            assert_eq!(message.payload(), b"sharded");
+                            seen.insert(message.connection());
+                        }
+                        std::thread::sleep(Duration::from_millis(5));
+                    }
+                    seen
+                })
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+
+    assert_eq!(received[0], shards[0]);
+    assert_eq!(received[1], shards[1]);
+    assert_eq!(
+        server
+            .receive_messages::<10>()
+            .expect("receive failed")
+            .count(),
+        0,
+        "sharded connections must no longer reach the server poll group"
+    );
+}
+
+#[test]
+fn test_attach_invalid_connection() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let group = gns_global
+        .create_poll_group()
+        .expect("create_poll_group failed");
+    assert!(matches!(
+        group.attach(GnsConnection::from_raw(0)),
+        Err(GnsError::Api(EResult::k_EResultInvalidParam))
+    ));
+}
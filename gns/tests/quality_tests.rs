@@ -0,0 +1,127 @@
+//! Tests for the connection quality monitor, see `QualityMonitor`.
+
+use gns::{
+    GnsConnection, GnsGlobal, GnsSocket, QualityEvent, QualityIssue, QualityMonitor, QualitySample,
+    QualityThresholds,
+};
+
+use std::time::Duration;
+
+fn ping(ping_ms: u32) -> QualitySample {
+    QualitySample {
+        ping_ms,
+        packet_loss_pct: Some(0.0),
+        jitter_ms: Some(1.0),
+        queue_time: Duration::ZERO,
+    }
+}
+
+/// A high ping is reported once, and recovers only under the recovery
+/// threshold.
+#[test]
+fn test_high_ping_hysteresis() {
+    let connection = GnsConnection::from_raw(1);
+    let mut monitor = QualityMonitor::new(QualityThresholds {
+        ping_ms: 100,
+        recovery_ratio: 0.5,
+        ..Default::default()
+    })
+    .with_window(1);
+
+    assert!(monitor.observe(connection, ping(80)).is_empty());
+    assert_eq!(
+        monitor.observe(connection, ping(120)),
+        vec![QualityEvent::HighPing {
+            connection,
+            ping_ms: 120
+        }]
+    );
+    assert!(monitor.observe(connection, ping(130)).is_empty());
+    // Under the threshold, but above the recovery one.
+    assert!(monitor.observe(connection, ping(70)).is_empty());
+    assert_eq!(monitor.issues(connection), &[QualityIssue::HighPing]);
+    assert_eq!(
+        monitor.observe(connection, ping(40)),
+        vec![QualityEvent::Recovered {
+            connection,
+            issue: QualityIssue::HighPing
+        }]
+    );
+    assert!(monitor.issues(connection).is_empty());
+}
+
+/// A single bad sample is smoothed out by the window.
+#[test]
+fn test_window_averages_samples() {
+    let connection = GnsConnection::from_raw(2);
+    let mut monitor = QualityMonitor::new(QualityThresholds::default()).with_window(4);
+    for _ in 0..3 {
+        assert!(monitor.observe(connection, ping(20)).is_empty());
+    }
+    let spike = QualitySample {
+        packet_loss_pct: Some(12.0),
+        ..ping(20)
+    };
+    assert!(monitor.observe(connection, spike).is_empty());
+    assert_eq!(
+        monitor.average(connection).unwrap().packet_loss_pct,
+        Some(3.0)
+    );
+
+    // Sustained loss is reported, along with the queue.
+    let congested = QualitySample {
+        packet_loss_pct: Some(12.0),
+        queue_time: Duration::from_secs(1),
+        ..ping(20)
+    };
+    let events = monitor.observe(connection, congested);
+    assert_eq!(
+        events,
+        vec![
+            QualityEvent::PacketLossSpike {
+                connection,
+                packet_loss_pct: 6.0
+            },
+            QualityEvent::QueueBackedUp {
+                connection,
+                queue_time: Duration::from_millis(250)
+            },
+        ]
+    );
+    assert!(events.iter().all(|event| event.connection() == connection));
+}
+
+/// Unknown values raise nothing.
+#[test]
+fn test_unknown_values_ignored() {
+    let connection = GnsConnection::from_raw(3);
+    let mut monitor = QualityMonitor::new(QualityThresholds::default()).with_window(1);
+    let unknown = QualitySample {
+        packet_loss_pct: None,
+        jitter_ms: None,
+        ..ping(10)
+    };
+    assert!(monitor.observe(connection, unknown).is_empty());
+    assert_eq!(monitor.average(connection), Some(unknown));
+}
+
+/// A healthy in-process connection raises nothing, and the monitor samples
+/// once per interval.
+#[test]
+fn test_monitor_socket_pair() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, _right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+    let mut monitor =
+        QualityMonitor::new(QualityThresholds::default()).with_interval(Duration::from_secs(60));
+
+    assert!(monitor.poll(&left).is_empty());
+    assert!(monitor.average(left.connection()).is_some());
+    assert!(monitor.issues(left.connection()).is_empty());
+
+    // Within the interval, nothing is sampled.
+    monitor.remove(left.connection());
+    assert!(monitor.poll(&left).is_empty());
+    assert!(monitor.average(left.connection()).is_none());
+}
@@ -9,53 +9,16 @@
 //! - dropping an iterator with unconsumed messages releases them without
 //!   double-freeing or leaking, and leaves the socket usable.
 
-use gns::sys::*;
-use gns::{GnsGlobal, GnsSocket, IsClient, IsServer, MessageSlot, SendFlags};
+use gns::{GnsGlobal, GnsSocket, IsClient, MessageSlot, SendFlags};
 
-use std::net::Ipv4Addr;
 use std::time::{Duration, Instant};
 
-mod common;
-use common::free_port;
-
-/// Establish a connected server/client pair, driven from a single thread.
-fn connected_pair() -> (
-    &'static GnsGlobal,
-    GnsSocket<IsServer>,
-    GnsSocket<IsClient>,
-) {
+/// Establish an in-process connected pair; no port is bound.
+fn connected_pair() -> (&'static GnsGlobal, GnsSocket<IsClient>, GnsSocket<IsClient>) {
     let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
-    let port = free_port();
-    let server = GnsSocket::new(gns_global)
-        .listen(Ipv4Addr::LOCALHOST.into(), port)
-        .expect("Failed to create server socket");
-    let client = GnsSocket::new(gns_global)
-        .connect(Ipv4Addr::LOCALHOST.into(), port)
-        .expect("Failed to create client socket");
-
-    let deadline = Instant::now() + Duration::from_secs(10);
-    let mut connected = false;
-    while !connected && Instant::now() < deadline {
-        gns_global.poll_callbacks();
-        for event in server.receive_events() {
-            if let (
-                ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_None,
-                ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connecting,
-            ) = (event.old_state(), event.info().state())
-            {
-                let _ = server.accept(event.connection());
-            }
-        }
-        for event in client.receive_events() {
-            if event.info().state()
-                == ESteamNetworkingConnectionState::k_ESteamNetworkingConnectionState_Connected
-            {
-                connected = true;
-            }
-        }
-        std::thread::sleep(Duration::from_millis(5));
-    }
-    assert!(connected, "client did not connect within the timeout");
+    let (server, client) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
     (gns_global, server, client)
 }
 
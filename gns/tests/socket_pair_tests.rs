@@ -0,0 +1,104 @@
+//! Tests for in-process connection pairs created by `GnsSocket::socket_pair`.
+
+use gns::{
+    ConnectionInfoFlags, ConnectionState, ConnectionTransition, EndReason, GnsGlobal, GnsLane,
+    GnsSocket, SendFlags,
+};
+
+use std::time::{Duration, Instant};
+
+/// Messages flow both ways on a pair, with and without the network loopback.
+#[test]
+fn test_socket_pair_exchanges_messages() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    for use_network_loopback in [false, true] {
+        let (left, right) = GnsSocket::new(gns_global)
+            .socket_pair(use_network_loopback)
+            .expect("Failed to create socket pair");
+        assert_eq!(
+            left.get_connection_info(left.connection())
+                .expect("connection info")
+                .state(),
+            ConnectionState::Connected
+        );
+
+        for (from, to) in [(&left, &right), (&right, &left)] {
+            let msg = gns_global.utils().allocate_message(
+                from.connection(),
+                SendFlags::RELIABLE,
+                &b"paired"[..],
+            );
+            from.send_message(msg).expect("send_message failed");
+
+            let mut received = None;
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while received.is_none() && Instant::now() < deadline {
+                gns_global.poll_callbacks();
+                received = to.receive_messages::<1>().expect("receive failed").next();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            let received = received.expect("message not received within the timeout");
+            assert_eq!(received.payload(), b"paired");
+            assert_eq!(received.connection(), to.connection());
+        }
+    }
+}
+
+/// Lanes can be configured on a pair, and closing one end is reported to
+/// the other through its event queue.
+#[test]
+fn test_socket_pair_lanes_and_close_events() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+
+    left.configure_connection_lanes(left.connection(), &[GnsLane::new(1, 1), GnsLane::new(2, 1)])
+        .expect("configure_connection_lanes failed");
+    let (_, lanes) = left
+        .get_connection_real_time_status(left.connection(), 2)
+        .expect("get_connection_real_time_status failed");
+    assert_eq!(lanes.len(), 2);
+
+    right
+        .close_connection(right.connection(), EndReason::App(7), None, false)
+        .expect("close_connection failed");
+
+    let mut closed = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while closed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        closed = left
+            .receive_events()
+            .find(|event| event.transition() == ConnectionTransition::ClosedByPeer);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let closed = closed.expect("close not reported to the peer");
+    assert_eq!(closed.info().end_reason(), EndReason::App(7));
+}
+
+/// The info of a pair reports how it is carried, and describes it the way
+/// GNS does in its debug output.
+#[test]
+fn test_socket_pair_connection_info() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    for use_network_loopback in [false, true] {
+        let (left, _right) = GnsSocket::new(gns_global)
+            .socket_pair(use_network_loopback)
+            .expect("Failed to create socket pair");
+        let info = left
+            .get_connection_info(left.connection())
+            .expect("connection info");
+        assert_eq!(
+            info.flags().contains(ConnectionInfoFlags::LOOPBACK_BUFFERS),
+            !use_network_loopback
+        );
+        if use_network_loopback {
+            assert!(!info.flags().contains(ConnectionInfoFlags::UNENCRYPTED));
+        }
+        assert_eq!(info.listen_socket(), None);
+        assert!(!info.description().is_empty());
+        assert_eq!(info.to_string(), info.description());
+        assert!(format!("{info:?}").contains(info.description()));
+    }
+}
@@ -0,0 +1,73 @@
+//! Tests for connection names and detailed status reports.
+
+use gns::{DetailedStatus, GnsConnection, GnsGlobal, GnsLane, GnsSocket};
+
+const REPORT: &str = "\
+Connection
+    Remote address: 127.0.0.1:27015
+Current rates:
+    Sent:  30.0 pkts/sec   2.0 K/sec
+    Recv:  29.5 pkts/sec   1.5 K/sec
+    Ping:12ms
+    Quality: 98.5%  (Dropped:1.25%  WeirdSeq:0.25%)
+    Max jitter:3.5ms
+    Send rate: 262144
+Lifetime stats:
+    Ping:40ms
+    Quality: 90.0%  (Dropped:10.00%  WeirdSeq:0.00%)
+";
+
+#[test]
+fn test_detailed_status_parse() {
+    let status = DetailedStatus::parse(REPORT);
+    assert_eq!(status.ping_ms, Some(12));
+    assert_eq!(status.packet_loss_pct, Some(1.25));
+    assert_eq!(status.max_jitter_ms, Some(3.5));
+    assert_eq!(status.send_rate, Some(262_144));
+    assert_eq!(status.out_bytes_per_sec, Some(2048.0));
+    assert_eq!(status.in_bytes_per_sec, Some(1536.0));
+    assert!(status.lanes.is_empty());
+    assert_eq!(
+        status.fields[0],
+        ("Remote address".to_string(), "127.0.0.1:27015".to_string())
+    );
+    assert_eq!(status.fields.len(), 9);
+
+    assert_eq!(DetailedStatus::parse(""), DetailedStatus::default());
+}
+
+#[test]
+fn test_connection_name_and_detailed_status() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let (left, right) = GnsSocket::new(gns_global)
+        .socket_pair(false)
+        .expect("Failed to create socket pair");
+    let connection = left.connection();
+
+    assert_eq!(left.connection_name(connection), Some(String::new()));
+    left.set_connection_name(connection, "player-42")
+        .expect("set_connection_name failed");
+    assert_eq!(
+        left.connection_name(connection).as_deref(),
+        Some("player-42")
+    );
+    assert!(left.set_connection_name(connection, "bad\0name").is_err());
+
+    let report = left
+        .detailed_status(connection)
+        .expect("detailed_status failed");
+    assert!(!report.is_empty());
+
+    left.configure_connection_lanes(connection, &[GnsLane::new(1, 1), GnsLane::new(2, 1)])
+        .expect("configure_connection_lanes failed");
+    let status = left
+        .detailed_status_parsed(connection, 2)
+        .expect("detailed_status_parsed failed");
+    assert_eq!(status.lanes.len(), 2);
+    assert!(status.ping_ms.is_some());
+    assert!(status.send_rate.is_some());
+
+    assert_eq!(left.connection_name(GnsConnection::default()), None);
+    assert!(left.detailed_status(GnsConnection::default()).is_err());
+    drop(right);
+}
@@ -0,0 +1,111 @@
+//! The debug output forwarded to `tracing`, see
+//! `GnsUtils::enable_tracing_output`.
+#![cfg(feature = "tracing")]
+
+use gns::sys::*;
+use gns::{GnsGlobal, GnsSocket};
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Target, level and fields of an event.
+type Collected = (String, Level, Vec<(String, String)>);
+
+#[derive(Default)]
+struct Fields(Vec<(String, String)>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_owned(), value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name().to_owned(), format!("{value:?}")));
+    }
+}
+
+/// Subscriber keeping every event.
+struct Collector(Arc<Mutex<Vec<Collected>>>);
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.0.lock().unwrap().push((
+            event.metadata().target().to_owned(),
+            *event.metadata().level(),
+            fields.0,
+        ));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/// Lines about a named connection carry its name as a field.
+#[test]
+fn test_tracing_output_with_connection_name() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    tracing::subscriber::set_global_default(Collector(Arc::clone(&events)))
+        .expect("Failed to set the subscriber");
+
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    gns_global.utils().enable_tracing_output(
+        ESteamNetworkingSocketsDebugOutputType::k_ESteamNetworkingSocketsDebugOutputType_Everything,
+    );
+
+    let named = |events: &[Collected]| {
+        events.iter().any(|(_, _, fields)| {
+            fields
+                .iter()
+                .any(|(key, value)| key == "connection_name" && value == "tracing-test")
+        })
+    };
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !named(&events.lock().unwrap()) && Instant::now() < deadline {
+        let (left, right) = GnsSocket::new(gns_global)
+            .socket_pair(true)
+            .expect("Failed to create socket pair");
+        left.set_connection_name(left.connection(), "tracing-test")
+            .expect("set_connection_name failed");
+        drop((left, right));
+        for _ in 0..10 {
+            gns_global.poll_callbacks();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+    gns_global.utils().disable_debug_output();
+
+    let events = events.lock().unwrap();
+    assert!(
+        events.iter().all(|(target, _, _)| target == "gns"),
+        "an event was emitted out of the gns target"
+    );
+    assert!(named(&events), "no event carried the connection name");
+    let (_, _, fields) = events
+        .iter()
+        .find(|(_, _, fields)| fields.iter().any(|(key, _)| key == "connection"))
+        .expect("no event carried the connection description");
+    let (_, description) = fields.iter().find(|(key, _)| key == "connection").unwrap();
+    assert!(
+        description.starts_with('#'),
+        "unexpected connection description {description:?}"
+    );
+}
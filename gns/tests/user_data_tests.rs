@@ -0,0 +1,82 @@
+//! Tests for per-connection user data, which is left to the user: setting it
+//! must not affect how connection events are routed.
+
+use gns::{config, ConnectionTransition, GnsGlobal, GnsSocket, GnsSocketBuilder, SendFlags};
+
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::free_port;
+
+#[test]
+fn test_user_data_round_trips_without_breaking_routing() {
+    let gns_global = GnsGlobal::get().expect("Failed to initialize GNS global");
+    let port = free_port();
+    let server = GnsSocket::new(gns_global)
+        .listen(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create server socket");
+    let client = GnsSocketBuilder::new(gns_global)
+        .option(config::CONNECTION_USER_DATA, 7)
+        .connect(Ipv4Addr::LOCALHOST.into(), port)
+        .expect("Failed to create client socket");
+    assert_eq!(client.connection_user_data(client.connection()), Some(7));
+
+    let mut received = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while received.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        for event in server.receive_events() {
+            if event.transition() == ConnectionTransition::IncomingRequest {
+                // Before accepting, so that later events are checked too.
+                server
+                    .set_connection_user_data(event.connection(), 0xdead_beef)
+                    .expect("set_connection_user_data failed");
+                server.accept(event.connection()).expect("accept failed");
+            }
+        }
+        for event in client.receive_events() {
+            if event.transition() == ConnectionTransition::Connected {
+                assert_eq!(event.info().user_data(), 7);
+                let msg = gns_global.utils().allocate_message(
+                    client.connection(),
+                    SendFlags::RELIABLE,
+                    &b"session"[..],
+                );
+                client.send_message(msg).expect("send_message failed");
+            }
+        }
+        received = server
+            .receive_messages::<1>()
+            .expect("receive_messages failed")
+            .next();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let received = received.expect("message not received within the timeout");
+    assert_eq!(received.connection_user_data(), 0xdead_beef);
+    let connection = received.connection();
+    assert_eq!(server.connection_user_data(connection), Some(0xdead_beef));
+
+    // Events of the connection still reach the server.
+    drop(client);
+    let mut closed = None;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while closed.is_none() && Instant::now() < deadline {
+        gns_global.poll_callbacks();
+        closed = server.receive_events().find(|event| {
+            event.connection() == connection
+                && event.transition() == ConnectionTransition::ClosedByPeer
+        });
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let closed = closed.expect("close not reported to the server");
+    assert_eq!(closed.info().user_data(), 0xdead_beef);
+
+    assert!(server
+        .set_connection_user_data(gns::GnsConnection::default(), 1)
+        .is_err());
+    assert_eq!(
+        server.connection_user_data(gns::GnsConnection::default()),
+        None
+    );
+}